@@ -0,0 +1,124 @@
+use serde::Serialize;
+use std::{fs, io, path};
+
+/// Files downloaded for a problem, kept in `cache_dir/<id>`
+#[derive(Serialize, PartialEq, Debug)]
+pub struct Entry {
+    pub id: String,
+    #[serde(skip)]
+    pub path: path::PathBuf,
+    pub size: u64,
+    pub zip: bool,
+    pub samples: bool,
+    pub main: bool,
+}
+
+impl Entry {
+    fn from_dir(path: path::PathBuf) -> io::Result<Entry> {
+        Ok(Entry {
+            id: path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+            size: dir_size(&path)?,
+            zip: path.join("problem.zip").is_file(),
+            samples: path.join("samples").is_dir(),
+            main: fs::read_dir(&path)?
+                .flatten()
+                .any(|e| e.path().file_stem() == Some("main".as_ref())),
+            path,
+        })
+    }
+
+    pub fn remove(&self) -> io::Result<()> {
+        fs::remove_dir_all(&self.path)
+    }
+}
+
+fn dir_size(path: &path::Path) -> io::Result<u64> {
+    let mut size = 0;
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        size += if metadata.is_dir() {
+            dir_size(&entry.path())?
+        } else {
+            metadata.len()
+        };
+    }
+    Ok(size)
+}
+
+pub fn list(cache_dir: &path::Path) -> io::Result<Vec<Entry>> {
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(cache_dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            entries.push(Entry::from_dir(path)?);
+        }
+    }
+    entries.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(entries)
+}
+
+/// Looks for the cached files of a problem, only among the cache entries to avoid removing anything else
+pub fn find(cache_dir: &path::Path, id: &str) -> io::Result<Option<Entry>> {
+    Ok(list(cache_dir)?.into_iter().find(|e| e.id == id))
+}
+
+/// Formats a size in bytes with a binary prefix
+pub fn human_size(size: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut size = size as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", size, UNITS[unit])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils;
+
+    #[test]
+    fn list_test() {
+        let tmp = test_utils::SelfCleaningTmp::new("cache", "list_test");
+        fs::create_dir_all(tmp.join("P00001_en").join("samples")).unwrap();
+        fs::write(tmp.join("P00001_en").join("problem.zip"), [0; 1000]).unwrap();
+        fs::write(tmp.join("P00001_en").join("samples").join("1.inp"), "24").unwrap();
+        fs::create_dir_all(tmp.join("P00000_ca")).unwrap();
+        fs::write(tmp.join("P00000_ca").join("main.cc"), "").unwrap();
+        fs::write(tmp.join("credentials"), "").unwrap();
+
+        let entries = list(&tmp).unwrap();
+        assert_eq!(
+            entries
+                .iter()
+                .map(|e| (e.id.as_str(), e.size, e.zip, e.samples, e.main))
+                .collect::<Vec<_>>(),
+            vec![
+                ("P00000_ca", 0, false, false, true),
+                ("P00001_en", 1002, true, true, false)
+            ]
+        );
+
+        assert!(find(&tmp, "../cache").unwrap().is_none());
+        find(&tmp, "P00001_en").unwrap().unwrap().remove().unwrap();
+        assert!(!tmp.join("P00001_en").exists());
+    }
+
+    #[test]
+    fn human_size_test() {
+        assert_eq!(human_size(512), "512 B");
+        assert_eq!(human_size(1536), "1.5 KiB");
+        assert_eq!(human_size(5 * 1024 * 1024), "5.0 MiB");
+    }
+}
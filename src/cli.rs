@@ -0,0 +1,300 @@
+use clap::{Parser, Subcommand, ValueEnum};
+use std::path;
+
+#[derive(Parser, Debug)]
+#[clap(name = "advocat", version, author, about)]
+pub struct Args {
+    /// Show debug messages
+    #[clap(short, long, global = true)]
+    pub debug: bool,
+
+    /// Wall-clock time limit for each test, in seconds
+    #[clap(short, long, global = true, value_name = "SECONDS")]
+    pub time_limit: Option<f64>,
+
+    /// CPU time limit for each test, in seconds
+    #[clap(long, global = true, value_name = "SECONDS")]
+    pub cpu_limit: Option<u64>,
+
+    /// Memory limit for each test, in megabytes
+    #[clap(short, long, global = true, value_name = "MEGABYTES")]
+    pub memory_limit: Option<u64>,
+
+    /// Number of tests to run in parallel [default: number of CPUs]
+    #[clap(short, long, global = true, value_name = "N")]
+    pub jobs: Option<usize>,
+
+    /// Format of the program output
+    #[clap(short, long, global = true, value_enum, default_value_t = Format::Human)]
+    pub format: Format,
+
+    /// Write the test results to a JUnit XML file
+    #[clap(long, global = true, value_name = "FILE")]
+    pub junit: Option<path::PathBuf>,
+
+    /// Source file of your solution, relative to the problem folder [default: main.cc]
+    #[clap(short, long, global = true, value_name = "FILE")]
+    pub source: Option<path::PathBuf>,
+
+    /// Name of the compiled binary [default: the source file with the .x extension]
+    #[clap(short, long, global = true, value_name = "FILE")]
+    pub output: Option<path::PathBuf>,
+
+    /// Compiler used to build your solution [default: chosen from the source file extension]
+    #[clap(short, long, global = true, value_name = "NAME")]
+    pub compiler: Option<String>,
+
+    /// Re-run the failing tests with a binary built with AddressSanitizer and UBSan
+    #[clap(long, global = true)]
+    pub sanitize: bool,
+
+    /// Keep testing the problem each time your code or tests change
+    #[clap(short, long, global = true)]
+    pub watch: bool,
+
+    /// Don't connect to jutge.org, using only the previously downloaded files
+    #[clap(long, global = true)]
+    pub offline: bool,
+
+    /// Offer to submit your solution to jutge.org when it passes all the tests
+    #[clap(long, global = true)]
+    pub auto_submit: bool,
+
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+}
+
+impl Args {
+    /// Option given that only applies to the test command, if the command is another one
+    pub fn test_only_option(&self) -> Option<&'static str> {
+        if matches!(self.command, None | Some(Command::Test)) {
+            None
+        } else if self.watch {
+            Some("--watch")
+        } else if self.auto_submit {
+            Some("--auto-submit")
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Subcommand, Clone, Debug, PartialEq)]
+pub enum Command {
+    /// Download, compile and test the problem in the current directory (default)
+    Test,
+    /// Download the problem resources without compiling or testing anything
+    Fetch,
+    /// Remove the compiled binary and the temporal files of the problem
+    Clean,
+    /// Show the configuration in use
+    Config,
+    /// Test every problem folder found under DIR (the current directory by default)
+    Batch {
+        #[clap(value_name = "DIR")]
+        dir: Option<path::PathBuf>,
+    },
+    /// Submit your solution to jutge.org and wait for the verdict
+    Submit,
+    /// List your previous submissions of the problem
+    History {
+        /// Download the code of the submission number N next to your source file
+        #[clap(long, value_name = "N")]
+        get: Option<u32>,
+    },
+    /// Download the statement of the problem
+    Statement {
+        /// Print the statement as plain text
+        #[clap(long)]
+        text: bool,
+    },
+    /// Inspect or remove the problem files downloaded from jutge.org
+    Cache {
+        #[clap(subcommand)]
+        action: CacheAction,
+    },
+}
+
+#[derive(Subcommand, Clone, Debug, PartialEq)]
+pub enum CacheAction {
+    /// Show the size and contents of the downloaded problems
+    List {
+        #[clap(value_name = "ID")]
+        id: Option<String>,
+    },
+    /// Remove the downloaded files of a problem, or of every problem if no ID is given
+    Clean {
+        #[clap(value_name = "ID")]
+        id: Option<String>,
+    },
+    /// Download again the files of a problem [default: the problem in the current directory]
+    Refresh {
+        #[clap(value_name = "ID")]
+        id: Option<String>,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    /// Coloured text meant to be read by humans
+    Human,
+    /// A JSON report printed to stdout when the run ends
+    Json,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn args_definition_test() {
+        Args::command().debug_assert();
+    }
+
+    #[test]
+    fn default_command_test() {
+        let args = Args::try_parse_from(["advocat"]).unwrap();
+        assert!(!args.debug);
+        assert_eq!(args.format, Format::Human);
+        assert_eq!(args.command, None);
+    }
+
+    #[test]
+    fn subcommand_test() {
+        let args = Args::try_parse_from(["advocat", "fetch", "--debug"]).unwrap();
+        assert!(args.debug);
+        assert_eq!(args.command, Some(Command::Fetch));
+
+        let args = Args::try_parse_from(["advocat", "batch", "problems"]).unwrap();
+        assert_eq!(
+            args.command,
+            Some(Command::Batch {
+                dir: Some(path::PathBuf::from("problems"))
+            })
+        );
+
+        let args = Args::try_parse_from(["advocat", "cache", "clean", "P00001_en"]).unwrap();
+        assert_eq!(
+            args.command,
+            Some(Command::Cache {
+                action: CacheAction::Clean {
+                    id: Some(String::from("P00001_en"))
+                }
+            })
+        );
+        assert!(Args::try_parse_from(["advocat", "cache"]).is_err());
+
+        let args = Args::try_parse_from(["advocat", "submit"]).unwrap();
+        assert_eq!(args.command, Some(Command::Submit));
+
+        let args = Args::try_parse_from(["advocat", "history", "--get", "3"]).unwrap();
+        assert_eq!(args.command, Some(Command::History { get: Some(3) }));
+
+        let args = Args::try_parse_from(["advocat", "statement", "--text"]).unwrap();
+        assert_eq!(args.command, Some(Command::Statement { text: true }));
+    }
+
+    #[test]
+    fn limits_test() {
+        let args = Args::try_parse_from(["advocat", "-t", "2.5", "--cpu-limit", "2", "-m", "256"])
+            .unwrap();
+        assert_eq!(args.time_limit, Some(2.5));
+        assert_eq!(args.cpu_limit, Some(2));
+        assert_eq!(args.memory_limit, Some(256));
+        assert!(Args::try_parse_from(["advocat", "--cpu-limit", "1.5"]).is_err());
+    }
+
+    #[test]
+    fn jobs_test() {
+        let args = Args::try_parse_from(["advocat", "test", "-j", "4"]).unwrap();
+        assert_eq!(args.jobs, Some(4));
+        assert_eq!(args.command, Some(Command::Test));
+    }
+
+    #[test]
+    fn format_test() {
+        let args = Args::try_parse_from(["advocat", "--format", "json"]).unwrap();
+        assert_eq!(args.format, Format::Json);
+        assert!(Args::try_parse_from(["advocat", "--format", "xml"]).is_err());
+    }
+
+    #[test]
+    fn junit_test() {
+        let args = Args::try_parse_from(["advocat", "--junit", "report.xml"]).unwrap();
+        assert_eq!(args.junit, Some(path::PathBuf::from("report.xml")));
+    }
+
+    #[test]
+    fn source_test() {
+        let args = Args::try_parse_from(["advocat", "-s", "fast.cc", "--output", "fast"]).unwrap();
+        assert_eq!(args.source, Some(path::PathBuf::from("fast.cc")));
+        assert_eq!(args.output, Some(path::PathBuf::from("fast")));
+
+        let args = Args::try_parse_from(["advocat", "--compiler", "clang++"]).unwrap();
+        assert_eq!(args.compiler, Some(String::from("clang++")));
+    }
+
+    #[test]
+    fn sanitize_test() {
+        assert!(
+            Args::try_parse_from(["advocat", "--sanitize"])
+                .unwrap()
+                .sanitize
+        );
+    }
+
+    #[test]
+    fn watch_test() {
+        assert!(!Args::try_parse_from(["advocat"]).unwrap().watch);
+        assert!(
+            Args::try_parse_from(["advocat", "test", "-w"])
+                .unwrap()
+                .watch
+        );
+
+        let test_only_option =
+            |args: &[&str]| Args::try_parse_from(args).unwrap().test_only_option();
+        assert_eq!(test_only_option(&["advocat", "-w"]), None);
+        assert_eq!(
+            test_only_option(&["advocat", "fetch", "--watch"]),
+            Some("--watch")
+        );
+        assert_eq!(
+            test_only_option(&["advocat", "-w", "batch"]),
+            Some("--watch")
+        );
+    }
+
+    #[test]
+    fn offline_test() {
+        assert!(!Args::try_parse_from(["advocat"]).unwrap().offline);
+        assert!(
+            Args::try_parse_from(["advocat", "fetch", "--offline"])
+                .unwrap()
+                .offline
+        );
+    }
+
+    #[test]
+    fn auto_submit_test() {
+        assert!(!Args::try_parse_from(["advocat"]).unwrap().auto_submit);
+        assert!(
+            Args::try_parse_from(["advocat", "--auto-submit"])
+                .unwrap()
+                .auto_submit
+        );
+        assert_eq!(
+            Args::try_parse_from(["advocat", "submit", "--auto-submit"])
+                .unwrap()
+                .test_only_option(),
+            Some("--auto-submit")
+        );
+    }
+
+    #[test]
+    fn unknown_argument_test() {
+        assert!(Args::try_parse_from(["advocat", "--foo"]).is_err());
+        assert!(Args::try_parse_from(["advocat", "foo"]).is_err());
+    }
+}
@@ -0,0 +1,174 @@
+use crate::compilation::{diagnostics, Backend, CompilationPass};
+use crate::{debug, problem};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{fs, path, time};
+
+/// Hash of the compilation inputs, with a fixed algorithm so it's the same for every build of
+/// the program
+#[derive(Default)]
+pub struct Fingerprint(Sha256);
+
+impl Fingerprint {
+    /// Each value is prefixed with its length, so that consecutive values can't be mixed up
+    pub fn add(&mut self, value: impl AsRef<[u8]>) {
+        let value = value.as_ref();
+        self.0.update((value.len() as u64).to_le_bytes());
+        self.0.update(value);
+    }
+
+    pub fn add_all<T: AsRef<[u8]>>(&mut self, values: impl ExactSizeIterator<Item = T>) {
+        self.0.update((values.len() as u64).to_le_bytes());
+        values.for_each(|value| self.add(value));
+    }
+
+    fn finish(self) -> String {
+        self.0
+            .finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Entry {
+    fingerprint: String,
+    output_modified: Option<time::SystemTime>,
+    passes: Vec<CachedPass>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct CachedPass {
+    pass: u8,
+    stderr: String,
+}
+
+fn entry_path(problem: &problem::Problem) -> path::PathBuf {
+    problem.tmp_dir.join("build.json")
+}
+
+fn output_modified(problem: &problem::Problem) -> Option<time::SystemTime> {
+    fs::metadata(&problem.output)
+        .and_then(|m| m.modified())
+        .ok()
+}
+
+/// Hash of everything that affects the compilation result
+pub fn fingerprint(backend: &dyn Backend, problem: &problem::Problem) -> Option<String> {
+    let mut fingerprint = Fingerprint::default();
+    fingerprint.add(env!("CARGO_PKG_VERSION"));
+    fingerprint.add(fs::read(&problem.source).ok()?);
+    if problem.has_main == Some(false) {
+        fingerprint.add(fs::read(problem.downloaded_main()).ok()?);
+    }
+    fingerprint.add(problem.output.to_string_lossy().as_bytes());
+    backend.fingerprint(&mut fingerprint);
+    Some(fingerprint.finish())
+}
+
+/// Returns the passes of the previous compilation if none of its inputs changed since then
+pub fn lookup(backend: &dyn Backend, problem: &problem::Problem) -> Option<Vec<CompilationPass>> {
+    let entry: Entry = serde_json::from_slice(&fs::read(entry_path(problem)).ok()?).ok()?;
+    if Some(entry.fingerprint) != fingerprint(backend, problem) {
+        debug!("The compilation inputs have changed");
+        return None;
+    }
+    // The output could have been removed or overwritten after compiling
+    if entry.output_modified.is_none() || entry.output_modified != output_modified(problem) {
+        debug!("The compilation output has changed");
+        return None;
+    }
+
+    Some(
+        entry
+            .passes
+            .into_iter()
+            .map(|p| CompilationPass {
+                pass: p.pass,
+                diagnostics: diagnostics::parse(&p.stderr),
+                stderr: p.stderr,
+            })
+            .collect(),
+    )
+}
+
+pub fn store(backend: &dyn Backend, problem: &problem::Problem, passes: &[CompilationPass]) {
+    let fingerprint = match fingerprint(backend, problem) {
+        Some(fingerprint) => fingerprint,
+        None => return,
+    };
+    let entry = Entry {
+        fingerprint,
+        output_modified: output_modified(problem),
+        passes: passes
+            .iter()
+            .map(|p| CachedPass {
+                pass: p.pass,
+                stderr: p.stderr.clone(),
+            })
+            .collect(),
+    };
+
+    let result = serde_json::to_vec(&entry)
+        .map_err(|e| e.to_string())
+        .and_then(|json| fs::write(entry_path(problem), json).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        debug!("Couldn't save the compilation cache: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils;
+
+    #[test]
+    fn fingerprint_test() {
+        let finish = |values: &[&str]| {
+            let mut fingerprint = Fingerprint::default();
+            values.iter().for_each(|value| fingerprint.add(value));
+            fingerprint.finish()
+        };
+        // It must not change between builds, or every cached compilation would be outdated
+        assert_eq!(
+            finish(&["a"]),
+            "ae6121c88ba555f64c3d812123eb799d128015541f850c5e9bf1d54c08ad8481"
+        );
+        assert_ne!(finish(&["ab", "c"]), finish(&["a", "bc"]));
+    }
+
+    #[test]
+    fn lookup_test() {
+        let tmp = test_utils::SelfCleaningTmp::new("cache", "lookup_test");
+        let mut problem = test_utils::get_problem("P00000_xx");
+        problem.source = tmp.join("main.cc");
+        problem.output = tmp.join("main.x");
+        problem.tmp_dir = tmp.to_path_buf();
+        let backend = problem.backend;
+        let passes = || {
+            vec![CompilationPass {
+                pass: 1,
+                stderr: String::from("main.cc:1:1: warning: careful"),
+                diagnostics: Vec::new(),
+            }]
+        };
+
+        fs::write(&problem.source, "int main() {}\n").unwrap();
+        assert!(lookup(backend, &problem).is_none());
+
+        fs::write(&problem.output, "").unwrap();
+        store(backend, &problem, &passes());
+        let cached = lookup(backend, &problem).unwrap();
+        assert_eq!(cached.len(), 1);
+        assert_eq!(cached[0].stderr, "main.cc:1:1: warning: careful");
+        assert_eq!(cached[0].diagnostics.len(), 1);
+
+        fs::write(&problem.source, "int main() { return 0; }\n").unwrap();
+        assert!(lookup(backend, &problem).is_none());
+
+        store(backend, &problem, &passes());
+        fs::remove_file(&problem.output).unwrap();
+        assert!(lookup(backend, &problem).is_none());
+    }
+}
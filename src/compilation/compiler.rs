@@ -1,7 +1,13 @@
-use crate::{debug, problem};
+use crate::compilation::{self, cache, diagnostics, template, Backend};
+use crate::{debug, problem, testing};
 use std::{fmt, io, path, process};
 
+const CXX_EXTENSIONS: &[&str] = &["cc", "cpp", "cxx"];
+
 pub static P1XX: Compiler = Compiler {
+    name: "g++",
+    jutge_id: "P1++",
+    extensions: CXX_EXTENSIONS,
     command: "g++",
     flags1: &[
         "-D_JUDGE_",
@@ -14,12 +20,69 @@ pub static P1XX: Compiler = Compiler {
         "-Wshadow",
     ],
     flags2: &["-D_JUDGE_", "-DNDEBUG", "-O2"],
+    libs: &[],
+    templates: &template::CXX_TEMPLATES,
+};
+
+pub static CLANGXX: Compiler = Compiler {
+    name: "clang++",
+    jutge_id: "CLang++17",
+    extensions: CXX_EXTENSIONS,
+    command: "clang++",
+    flags1: &[
+        "-std=c++17",
+        "-D_JUDGE_",
+        "-DNDEBUG",
+        "-O2",
+        "-Wall",
+        "-Wextra",
+        "-Werror",
+        "-Wno-sign-compare",
+        "-Wshadow",
+    ],
+    flags2: &["-std=c++17", "-D_JUDGE_", "-DNDEBUG", "-O2"],
+    libs: &[],
+    templates: &template::CXX_TEMPLATES,
+};
+
+pub static GCC: Compiler = Compiler {
+    name: "gcc",
+    jutge_id: "GCC",
+    extensions: &["c"],
+    command: "gcc",
+    flags1: &[
+        "-std=c99",
+        "-D_JUDGE_",
+        "-DNDEBUG",
+        "-O2",
+        "-Wall",
+        "-Wextra",
+        "-Werror",
+        "-Wno-sign-compare",
+        "-Wshadow",
+    ],
+    flags2: &["-std=c99", "-D_JUDGE_", "-DNDEBUG", "-O2"],
+    libs: &["-lm"],
+    templates: &template::C_TEMPLATES,
 };
 
-pub struct Compiler<'a> {
-    command: &'a str,
-    flags1: &'a [&'a str],
-    flags2: &'a [&'a str],
+const SANITIZER_FLAGS: &[&str] = &[
+    "-fsanitize=address,undefined",
+    "-fno-omit-frame-pointer",
+    "-g",
+];
+
+/// The built-in compilers use static strings, while the ones defined in the config file own them
+#[derive(Clone)]
+pub struct Compiler<S = &'static str, F = &'static [&'static str]> {
+    name: S,
+    jutge_id: &'static str,
+    extensions: &'static [&'static str],
+    command: S,
+    flags1: F,
+    flags2: F,
+    libs: F,
+    templates: &'static template::Templates,
 }
 
 pub enum CompilationError {
@@ -29,6 +92,7 @@ pub enum CompilationError {
     ExecutionError(io::Error),
     CompilerError(String),
     MissingOutput,
+    TemplateError(template::Error),
 }
 
 impl fmt::Display for CompilationError {
@@ -42,13 +106,24 @@ impl fmt::Display for CompilationError {
                 write!(f, "The compiler raised an error:\n{}", stderr)
             }
             CompilationError::MissingOutput => write!(f, "Can't find the compiler output!"),
+            CompilationError::TemplateError(e) => {
+                write!(f, "Couldn't generate the sources to compile: {}", e)
+            }
         }
     }
 }
 
+pub struct CompilationPass {
+    pub pass: u8,
+    pub stderr: String,
+    pub diagnostics: Vec<diagnostics::Diagnostic>,
+}
+
 pub struct CompileProcessError {
     pub pass: u8,
     pub error: CompilationError,
+    pub diagnostics: Vec<diagnostics::Diagnostic>,
+    pub completed_passes: Vec<CompilationPass>,
 }
 
 impl fmt::Display for CompileProcessError {
@@ -62,89 +137,212 @@ enum CompilationType {
     Binary,
 }
 
-impl Compiler<'_> {
-    fn run(
+const BASE_COMPILERS: [&Compiler; 3] = [&P1XX, &CLANGXX, &GCC];
+
+impl Compiler<String, Vec<String>> {
+    pub fn from_profile(profile: compilation::Profile) -> Option<Self> {
+        let base = BASE_COMPILERS.iter().find(|c| c.name == profile.base)?;
+        let to_owned = |flags: &[&str]| flags.iter().map(|f| f.to_string()).collect();
+
+        Some(Compiler {
+            name: profile.name,
+            jutge_id: base.jutge_id,
+            extensions: base.extensions,
+            command: profile.command.unwrap_or_else(|| base.command.to_owned()),
+            flags1: profile.flags1.unwrap_or_else(|| to_owned(base.flags1)),
+            flags2: profile.flags2.unwrap_or_else(|| to_owned(base.flags2)),
+            libs: profile.libs.unwrap_or_else(|| to_owned(base.libs)),
+            templates: base.templates,
+        })
+    }
+}
+
+impl<S: AsRef<str>, F: AsRef<[S]>> Compiler<S, F> {
+    fn run<T: AsRef<str>>(
         &self,
         source: &path::Path,
         output: &path::Path,
         compilation_type: CompilationType,
-        flags: &[&str],
-    ) -> Result<(), CompilationError> {
-        if !source.exists() {
-            Err(CompilationError::SourceDoesNotExist)
-        } else if source.is_dir() {
-            Err(CompilationError::SourceIsADir)
-        } else if output.is_dir() {
-            Err(CompilationError::OutputIsADir)
-        } else {
-            let mut command = process::Command::new(&self.command);
-            command
-                .args(flags)
-                .args(["-o", output.to_string_lossy().as_ref()]);
-
-            match compilation_type {
-                CompilationType::Object => command.args(["-c", source.to_string_lossy().as_ref()]),
-                CompilationType::Binary => command.arg(source.to_string_lossy().to_string()),
-            };
-
-            debug!("Running command: {:?}", command);
-            let command = command.output().map_err(CompilationError::ExecutionError)?;
-
-            if command.status.success() {
-                if output.is_file() {
-                    Ok(())
-                } else {
-                    Err(CompilationError::MissingOutput)
-                }
-            } else {
-                Err(CompilationError::CompilerError(
-                    String::from_utf8_lossy(&command.stderr).to_string(),
-                ))
-            }
-        }
+        flags: &[T],
+    ) -> Result<String, CompilationError> {
+        check_paths(source, output)?;
+
+        let mut command = process::Command::new(self.command.as_ref());
+        command
+            .args(flags.iter().map(T::as_ref))
+            .args(["-o", output.to_string_lossy().as_ref()]);
+
+        match compilation_type {
+            CompilationType::Object => command.args(["-c", source.to_string_lossy().as_ref()]),
+            CompilationType::Binary => command
+                .arg(source.to_string_lossy().to_string())
+                .args(self.libs.as_ref().iter().map(S::as_ref)),
+        };
+
+        run_command(command, output)
+    }
+
+    fn generate_source(
+        &self,
+        problem: &problem::Problem,
+    ) -> Result<path::PathBuf, CompilationError> {
+        debug!("Generating sources...");
+        template::generate_main(problem, self.templates).map_err(CompilationError::TemplateError)
     }
 
     fn compile_first_pass(
         &self,
         source: &path::Path,
         output: &path::Path,
-    ) -> Result<(), CompilationError> {
-        self.run(source, output, CompilationType::Object, self.flags1)
+    ) -> Result<String, CompilationError> {
+        self.run(
+            source,
+            output,
+            CompilationType::Object,
+            self.flags1.as_ref(),
+        )
     }
 
     fn compile_and_link_first_pass(
         &self,
         source: &path::Path,
         output: &path::Path,
-    ) -> Result<(), CompilationError> {
-        self.run(source, output, CompilationType::Binary, self.flags1)
+    ) -> Result<String, CompilationError> {
+        self.run(
+            source,
+            output,
+            CompilationType::Binary,
+            self.flags1.as_ref(),
+        )
     }
 
     fn compile_and_link_second_pass(
         &self,
         source: &path::Path,
         output: &path::Path,
-    ) -> Result<(), CompilationError> {
-        self.run(source, output, CompilationType::Binary, self.flags2)
+    ) -> Result<String, CompilationError> {
+        self.run(
+            source,
+            output,
+            CompilationType::Binary,
+            self.flags2.as_ref(),
+        )
     }
+}
 
-    pub fn compile_problem(
-        &self,
-        problem: &problem::Problem,
-        generated_source: &path::Path,
-    ) -> Result<(), CompileProcessError> {
-        debug!("Running the first pass compilation (P1++ checks)");
-        if problem.has_main {
+impl<S, F> Backend for Compiler<S, F>
+where
+    S: AsRef<str> + Sync,
+    F: AsRef<[S]> + Sync,
+{
+    fn name(&self) -> &str {
+        self.name.as_ref()
+    }
+
+    fn jutge_id(&self) -> &'static str {
+        self.jutge_id
+    }
+
+    fn command(&self) -> &str {
+        self.command.as_ref()
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        self.extensions
+    }
+
+    fn allows_nomain(&self) -> bool {
+        true
+    }
+
+    fn check(&self, problem: &problem::Problem) -> Result<String, CompilationError> {
+        debug!(
+            "Running the first pass compilation ({} checks)",
+            self.jutge_id
+        );
+        if problem.has_main != Some(false) {
             let output = problem.tmp_dir.join("main.x");
             self.compile_and_link_first_pass(problem.source.as_path(), output.as_path())
         } else {
             let output = problem.tmp_dir.join("main.o");
             self.compile_first_pass(problem.source.as_path(), output.as_path())
         }
-        .map_err(|error| CompileProcessError { pass: 1, error })?;
+    }
+
+    fn build(&self, problem: &problem::Problem) -> Option<Result<String, CompilationError>> {
+        let generated_source = match self.generate_source(problem) {
+            Ok(generated_source) => generated_source,
+            Err(e) => return Some(Err(e)),
+        };
 
-        debug!("Running the second pass compilation (G++ binary)");
-        self.compile_and_link_second_pass(generated_source, problem.output.as_path())
-            .map_err(|error| CompileProcessError { pass: 2, error })
+        debug!(
+            "Running the second pass compilation ({} binary)",
+            self.command.as_ref()
+        );
+        Some(self.compile_and_link_second_pass(&generated_source, problem.output.as_path()))
+    }
+
+    fn build_sanitized(
+        &self,
+        problem: &problem::Problem,
+    ) -> Option<Result<testing::Program, CompilationError>> {
+        let generated_source = match self.generate_source(problem) {
+            Ok(generated_source) => generated_source,
+            Err(e) => return Some(Err(e)),
+        };
+
+        debug!("Compiling a binary with sanitizers");
+        let output = problem.tmp_dir.join("sanitized.x");
+        let flags: Vec<&str> = (self.flags2.as_ref().iter().map(S::as_ref))
+            .chain(SANITIZER_FLAGS.iter().copied())
+            .collect();
+        Some(
+            self.run(&generated_source, &output, CompilationType::Binary, &flags)
+                .map(|_| testing::Program::binary(&output)),
+        )
+    }
+
+    fn program(&self, problem: &problem::Problem) -> testing::Program {
+        testing::Program::binary(problem.output.as_path())
+    }
+
+    fn fingerprint(&self, fingerprint: &mut cache::Fingerprint) {
+        fingerprint.add(self.jutge_id);
+        fingerprint.add(self.command.as_ref());
+        for flags in [&self.flags1, &self.flags2, &self.libs] {
+            fingerprint.add_all(flags.as_ref().iter().map(S::as_ref));
+        }
+        self.templates.fingerprint(fingerprint);
+    }
+}
+
+pub fn check_paths(source: &path::Path, output: &path::Path) -> Result<(), CompilationError> {
+    if !source.exists() {
+        Err(CompilationError::SourceDoesNotExist)
+    } else if source.is_dir() {
+        Err(CompilationError::SourceIsADir)
+    } else if output.is_dir() {
+        Err(CompilationError::OutputIsADir)
+    } else {
+        Ok(())
+    }
+}
+
+pub fn run_command(
+    mut command: process::Command,
+    output: &path::Path,
+) -> Result<String, CompilationError> {
+    debug!("Running command: {:?}", command);
+    let command = command.output().map_err(CompilationError::ExecutionError)?;
+    let stderr = String::from_utf8_lossy(&command.stderr).to_string();
+
+    if command.status.success() {
+        if output.is_file() {
+            Ok(stderr)
+        } else {
+            Err(CompilationError::MissingOutput)
+        }
+    } else {
+        Err(CompilationError::CompilerError(stderr))
     }
 }
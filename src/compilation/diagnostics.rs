@@ -0,0 +1,174 @@
+use regex::Regex;
+use serde::Serialize;
+use std::fmt::Write;
+use std::{env, path};
+use termion::{color, style};
+
+#[derive(Serialize, PartialEq, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+#[derive(Serialize, PartialEq, Clone, Debug)]
+pub struct Diagnostic {
+    pub file: path::PathBuf,
+    pub line: usize,
+    pub column: Option<usize>,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// Parses the `file:line[:column]: severity: message` lines of the compiler output
+pub fn parse(stderr: &str) -> Vec<Diagnostic> {
+    let re = Regex::new(
+        r"^(?P<file>[^\s:][^:]*):(?P<line>\d+):(?:(?P<column>\d+):)? (?P<severity>fatal error|error|warning|note): ?(?P<message>.*)$",
+    )
+    .unwrap();
+
+    let mut diagnostics = Vec::new();
+    let mut lines = stderr.lines().peekable();
+    while let Some(line) = lines.next() {
+        let captures = match re.captures(line) {
+            Some(captures) => captures,
+            None => continue,
+        };
+
+        let mut message = captures["message"].trim().to_owned();
+        if message.is_empty() || message.starts_with('[') {
+            // Some compilers (like ghc) write the message in the following lines
+            if let Some(next) = lines.peek() {
+                message = next.trim().to_owned();
+            }
+        }
+
+        diagnostics.push(Diagnostic {
+            file: path::PathBuf::from(&captures["file"]),
+            line: captures["line"].parse().unwrap_or_default(),
+            column: captures
+                .name("column")
+                .and_then(|c| c.as_str().parse().ok()),
+            severity: match &captures["severity"] {
+                "warning" => Severity::Warning,
+                "note" => Severity::Note,
+                _ => Severity::Error,
+            },
+            message,
+        });
+    }
+
+    diagnostics
+}
+
+pub fn render(diagnostics: &[Diagnostic]) -> String {
+    let mut rendered = String::new();
+    for diagnostic in diagnostics {
+        let (severity, severity_color): (&str, &dyn color::Color) = match diagnostic.severity {
+            Severity::Error => ("error", &color::Red),
+            Severity::Warning => ("warning", &color::Yellow),
+            Severity::Note => ("note", &color::Cyan),
+        };
+        // The paths are usually absolute, so they are shown from the current directory if possible
+        let file = env::current_dir()
+            .ok()
+            .and_then(|dir| diagnostic.file.strip_prefix(dir).ok())
+            .unwrap_or(&diagnostic.file)
+            .to_string_lossy();
+        let position = match diagnostic.column {
+            Some(column) => format!("{}:{}:{}", file, diagnostic.line, column),
+            None => format!("{}:{}", file, diagnostic.line),
+        };
+
+        let _ = writeln!(
+            rendered,
+            "{}{}{} {}{}:{} {}",
+            style::Bold,
+            position,
+            style::Reset,
+            color::Fg(severity_color),
+            severity,
+            style::Reset,
+            diagnostic.message
+        );
+    }
+    rendered.trim_end().to_owned()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_gcc_test() {
+        let stderr = "/tmp/P00000_xx/main.cc: In function 'int main()':
+/tmp/P00000_xx/main.cc:3:5: error: 'x' was not declared in this scope
+    3 |     x = 1;
+      |     ^
+/tmp/P00000_xx/main.cc:4:9: warning: unused variable 'y' [-Wunused-variable]
+In file included from main.cc:1:
+main.java:7: error: ';' expected
+/usr/bin/ld: main.o: undefined reference to `f()'
+";
+        assert_eq!(
+            parse(stderr),
+            vec![
+                Diagnostic {
+                    file: path::PathBuf::from("/tmp/P00000_xx/main.cc"),
+                    line: 3,
+                    column: Some(5),
+                    severity: Severity::Error,
+                    message: String::from("'x' was not declared in this scope"),
+                },
+                Diagnostic {
+                    file: path::PathBuf::from("/tmp/P00000_xx/main.cc"),
+                    line: 4,
+                    column: Some(9),
+                    severity: Severity::Warning,
+                    message: String::from("unused variable 'y' [-Wunused-variable]"),
+                },
+                Diagnostic {
+                    file: path::PathBuf::from("main.java"),
+                    line: 7,
+                    column: None,
+                    severity: Severity::Error,
+                    message: String::from("';' expected"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn render_test() {
+        let diagnostic = Diagnostic {
+            file: env::current_dir()
+                .unwrap()
+                .join("P00000_xx")
+                .join("main.cc"),
+            line: 3,
+            column: Some(5),
+            severity: Severity::Error,
+            message: String::from("'x' was not declared in this scope"),
+        };
+        let rendered = render(&[diagnostic]);
+        assert!(rendered.contains(
+            path::Path::new("P00000_xx")
+                .join("main.cc:3:5")
+                .to_str()
+                .unwrap()
+        ));
+        assert!(rendered.ends_with("'x' was not declared in this scope"));
+        assert!(!rendered.contains(env::current_dir().unwrap().to_str().unwrap()));
+    }
+
+    #[test]
+    fn parse_ghc_test() {
+        let stderr = "main.hs:2:8: error: [GHC-88464]
+    Variable not in scope: x :: IO ()
+";
+        let diagnostics = parse(stderr);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "Variable not in scope: x :: IO ()");
+    }
+}
@@ -0,0 +1,94 @@
+use crate::compilation::compiler::{check_paths, run_command, CompilationError};
+use crate::compilation::Backend;
+use crate::{debug, problem, testing};
+use std::process;
+
+pub static GHC: Haskell = Haskell { command: "ghc" };
+
+pub struct Haskell<'a> {
+    command: &'a str,
+}
+
+impl Backend for Haskell<'_> {
+    fn name(&self) -> &'static str {
+        "ghc"
+    }
+
+    fn jutge_id(&self) -> &'static str {
+        "GHC"
+    }
+
+    fn command(&self) -> &str {
+        self.command
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["hs"]
+    }
+
+    fn check(&self, problem: &problem::Problem) -> Result<String, CompilationError> {
+        check_paths(&problem.source, &problem.output)?;
+
+        debug!("Running the first pass compilation (GHC binary)");
+        let mut command = process::Command::new(self.command);
+        command
+            .args(["-O3", "-outputdir"])
+            .arg(problem.tmp_dir.join("ghc"))
+            .arg("-o")
+            .arg(&problem.output)
+            .arg(&problem.source);
+        run_command(command, &problem.output)
+    }
+
+    fn program(&self, problem: &problem::Problem) -> testing::Program {
+        testing::Program::binary(problem.output.as_path())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils;
+    use std::fs;
+
+    #[test]
+    fn program_test() {
+        // Unlike the C and C++ compilers, ghc isn't installed in most machines
+        if process::Command::new(GHC.command)
+            .arg("--version")
+            .output()
+            .is_err()
+        {
+            eprintln!("Skipping the test: ghc isn't installed");
+            return;
+        }
+
+        let tmp = test_utils::SelfCleaningTmp::new("haskell", "program_test");
+        let mut problem = test_utils::get_problem("P00000_xx");
+        problem.source = tmp.join("main.hs");
+        problem.output = GHC.default_output(&problem.source, &tmp);
+        problem.tmp_dir = tmp.to_path_buf();
+
+        fs::write(&problem.source, "main = putStrLn \"x\" >> print y\n").unwrap();
+        match GHC.check(&problem) {
+            Err(CompilationError::CompilerError(stderr)) => assert!(!stderr.is_empty()),
+            _ => panic!(),
+        }
+
+        fs::write(&problem.source, "main = getLine >>= putStrLn . reverse\n").unwrap();
+        fs::write(tmp.join("sample.inp"), "abc\n").unwrap();
+        fs::write(tmp.join("sample.cor"), "cba\n").unwrap();
+        assert!(GHC.check(&problem).is_ok());
+        assert!(problem.output.is_file());
+
+        let suite = testing::TestSuite::from_dir("haskell", &tmp).ok().unwrap();
+        let (summary, _) = suite.run(
+            &GHC.program(&problem),
+            None,
+            false,
+            &testing::Limits::default(),
+            1,
+        );
+        assert_eq!(summary.passed, 1);
+    }
+}
@@ -0,0 +1,93 @@
+use crate::compilation::compiler::{check_paths, run_command, CompilationError};
+use crate::compilation::Backend;
+use crate::{debug, problem, testing};
+use std::{path, process};
+
+// Compiles the script to bytecode, reporting syntax errors without a traceback
+const PY_COMPILE: &str = "import py_compile, sys
+try:
+    py_compile.compile(sys.argv[1], cfile=sys.argv[2], doraise=True)
+except py_compile.PyCompileError as e:
+    sys.exit(e.msg)";
+
+pub static PYTHON3: Interpreter = Interpreter { command: "python3" };
+
+pub struct Interpreter<'a> {
+    command: &'a str,
+}
+
+impl Interpreter<'_> {
+    fn compile(
+        &self,
+        source: &path::Path,
+        output: &path::Path,
+    ) -> Result<String, CompilationError> {
+        check_paths(source, output)?;
+
+        let mut command = process::Command::new(self.command);
+        command.args(["-c", PY_COMPILE]).arg(source).arg(output);
+        run_command(command, output)
+    }
+}
+
+impl Backend for Interpreter<'_> {
+    fn name(&self) -> &'static str {
+        "python3"
+    }
+
+    fn jutge_id(&self) -> &'static str {
+        "Python3"
+    }
+
+    fn command(&self) -> &str {
+        self.command
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["py"]
+    }
+
+    fn produces_binary(&self) -> bool {
+        false
+    }
+
+    // The bytecode is only used to check the syntax, so it's kept with the temporal files
+    fn default_output(&self, source: &path::Path, tmp_dir: &path::Path) -> path::PathBuf {
+        tmp_dir
+            .join(source.file_stem().unwrap_or_default())
+            .with_extension("pyc")
+    }
+
+    fn check(&self, problem: &problem::Problem) -> Result<String, CompilationError> {
+        debug!("Running the first pass compilation (Python syntax checks)");
+        self.compile(problem.source.as_path(), problem.output.as_path())
+    }
+
+    fn program(&self, problem: &problem::Problem) -> testing::Program {
+        testing::Program::interpreted(self.command, problem.source.as_path())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils;
+    use std::fs;
+
+    #[test]
+    fn check_test() {
+        let tmp = test_utils::SelfCleaningTmp::new("interpreter", "check_test");
+        let source = tmp.join("main.py");
+        let output = tmp.join("main.pyc");
+
+        fs::write(&source, "print(input())\n").unwrap();
+        assert!(PYTHON3.compile(&source, &output).is_ok());
+        assert!(output.is_file());
+
+        fs::write(&source, "print(input()\n").unwrap();
+        match PYTHON3.compile(&source, &output) {
+            Err(CompilationError::CompilerError(stderr)) => assert!(!stderr.is_empty()),
+            _ => panic!(),
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use crate::compilation::compiler::{check_paths, run_command, CompilationError};
+use crate::compilation::{cache, Backend};
+use crate::{debug, problem, testing};
+use std::{path, process};
+
+// jutge.org expects the solutions to define their main() in this class
+const MAIN_CLASS: &str = "Main";
+
+pub static JDK: Java = Java {
+    compiler: "javac",
+    runtime: "java",
+};
+
+pub struct Java<'a> {
+    compiler: &'a str,
+    runtime: &'a str,
+}
+
+impl Backend for Java<'_> {
+    fn name(&self) -> &'static str {
+        "javac"
+    }
+
+    fn jutge_id(&self) -> &'static str {
+        "JDK"
+    }
+
+    fn command(&self) -> &str {
+        self.compiler
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["java"]
+    }
+
+    fn produces_binary(&self) -> bool {
+        false
+    }
+
+    fn default_output(&self, _source: &path::Path, tmp_dir: &path::Path) -> path::PathBuf {
+        tmp_dir
+            .join("java")
+            .join(MAIN_CLASS)
+            .with_extension("class")
+    }
+
+    fn check(&self, problem: &problem::Problem) -> Result<String, CompilationError> {
+        check_paths(&problem.source, &problem.output)?;
+
+        debug!("Running the first pass compilation (javac classes)");
+        let mut command = process::Command::new(self.compiler);
+        command
+            .args(["-Xlint", "-d"])
+            .arg(problem.output.parent().unwrap())
+            .arg(&problem.source);
+        run_command(command, &problem.output)
+    }
+
+    fn program(&self, problem: &problem::Problem) -> testing::Program {
+        testing::Program::virtual_machine(
+            self.runtime,
+            "-Xmx",
+            &[
+                "-cp".as_ref(),
+                problem.output.parent().unwrap().as_os_str(),
+                MAIN_CLASS.as_ref(),
+            ],
+        )
+    }
+
+    fn fingerprint(&self, fingerprint: &mut cache::Fingerprint) {
+        fingerprint.add(self.jutge_id());
+        fingerprint.add(self.compiler);
+        fingerprint.add(self.runtime);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils;
+    use std::fs;
+
+    #[test]
+    fn check_test() {
+        let tmp = test_utils::SelfCleaningTmp::new("java", "check_test");
+        let mut problem = test_utils::get_problem("P00000_xx");
+        problem.source = tmp.join("main.java");
+        problem.output = JDK.default_output(&problem.source, &tmp);
+        problem.tmp_dir = tmp.to_path_buf();
+
+        fs::write(
+            &problem.source,
+            "class Main { public static void main(String[] args) { System.out.println(1); } }\n",
+        )
+        .unwrap();
+        assert!(JDK.check(&problem).is_ok());
+        assert!(problem.output.is_file());
+
+        fs::write(&problem.source, "class Main {\n").unwrap();
+        match JDK.check(&problem) {
+            Err(CompilationError::CompilerError(stderr)) => assert!(!stderr.is_empty()),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn program_test() {
+        let tmp = test_utils::SelfCleaningTmp::new("java", "program_test");
+        let mut problem = test_utils::get_problem("P00000_xx");
+        problem.source = tmp.join("main.java");
+        problem.output = JDK.default_output(&problem.source, &tmp);
+        problem.tmp_dir = tmp.to_path_buf();
+
+        fs::write(
+            &problem.source,
+            "class Main { public static void main(String[] args) { System.out.println(1); } }\n",
+        )
+        .unwrap();
+        fs::write(tmp.join("sample.inp"), "").unwrap();
+        fs::write(tmp.join("sample.cor"), "1\n").unwrap();
+        assert!(JDK.check(&problem).is_ok());
+
+        // The JVM can't start if its address space is limited to the default memory limit
+        let suite = testing::TestSuite::from_dir("java", &tmp).ok().unwrap();
+        let (summary, _) = suite.run(
+            &JDK.program(&problem),
+            None,
+            false,
+            &testing::Limits::default(),
+            1,
+        );
+        assert_eq!(summary.passed, 1);
+    }
+}
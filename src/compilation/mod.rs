@@ -1,6 +1,210 @@
+use crate::{debug, problem, testing};
+use std::{fmt, path};
+
+mod cache;
 mod compiler;
+mod diagnostics;
+mod haskell;
+mod interpreter;
+mod java;
 mod template;
 
 pub use compiler::CompilationError as Error;
-pub use compiler::P1XX;
-pub use template::generate_main;
+pub use compiler::{CompilationPass, CompileProcessError};
+pub use diagnostics::{render as render_diagnostics, Diagnostic};
+
+pub static BACKENDS: &[&dyn Backend] = &[
+    &compiler::P1XX,
+    &compiler::CLANGXX,
+    &compiler::GCC,
+    &haskell::GHC,
+    &java::JDK,
+    &interpreter::PYTHON3,
+];
+
+/// Compiler defined in a profile of the config file
+pub type ProfileCompiler = compiler::Compiler<String, Vec<String>>;
+
+/// A compiler defined in the config file, based on one of the built-in C and C++ compilers
+pub struct Profile {
+    pub name: String,
+    pub base: String,
+    pub command: Option<String>,
+    pub flags1: Option<Vec<String>>,
+    pub flags2: Option<Vec<String>>,
+    pub libs: Option<Vec<String>>,
+}
+
+pub trait Backend: Sync {
+    /// Name used to choose the backend from the command line or the config file
+    fn name(&self) -> &str;
+
+    /// Id of the equivalent compiler at jutge.org
+    fn jutge_id(&self) -> &'static str;
+
+    /// Program that compiles or interprets the solutions
+    fn command(&self) -> &str;
+
+    /// Source file extensions handled by this backend, the first one being the default
+    fn extensions(&self) -> &'static [&'static str];
+
+    /// Whether jutge.org provides a main() for the solutions that don't have one
+    fn allows_nomain(&self) -> bool {
+        false
+    }
+
+    /// Whether the output is a binary the user can keep in the problem folder
+    fn produces_binary(&self) -> bool {
+        true
+    }
+
+    fn default_output(&self, source: &path::Path, _tmp_dir: &path::Path) -> path::PathBuf {
+        source.with_extension("x")
+    }
+
+    /// First compilation pass, checking the user code as strictly as jutge.org does
+    fn check(&self, problem: &problem::Problem) -> Result<String, Error>;
+
+    /// Second compilation pass, building the program to test when the check pass doesn't
+    fn build(&self, _problem: &problem::Problem) -> Option<Result<String, Error>> {
+        None
+    }
+
+    /// Builds an additional program instrumented with sanitizers, if the backend supports them
+    fn build_sanitized(
+        &self,
+        _problem: &problem::Problem,
+    ) -> Option<Result<testing::Program, Error>> {
+        None
+    }
+
+    fn program(&self, problem: &problem::Problem) -> testing::Program;
+
+    /// Adds the settings that affect the compilation, to know when a cached build is outdated
+    fn fingerprint(&self, fingerprint: &mut cache::Fingerprint) {
+        fingerprint.add(self.name());
+        fingerprint.add(self.jutge_id());
+        fingerprint.add(self.command());
+    }
+
+    fn compile_problem(
+        &self,
+        problem: &problem::Problem,
+    ) -> Result<Vec<CompilationPass>, CompileProcessError> {
+        let process_error = |pass, error: Error, completed_passes| CompileProcessError {
+            pass,
+            diagnostics: match &error {
+                Error::CompilerError(stderr) => diagnostics::parse(stderr),
+                _ => Vec::new(),
+            },
+            error,
+            completed_passes,
+        };
+
+        let stderr = self
+            .check(problem)
+            .map_err(|error| process_error(1, error, Vec::new()))?;
+        let mut passes = vec![CompilationPass {
+            pass: 1,
+            diagnostics: diagnostics::parse(&stderr),
+            stderr,
+        }];
+
+        match self.build(problem) {
+            None => Ok(passes),
+            Some(Ok(stderr)) => {
+                passes.push(CompilationPass {
+                    pass: 2,
+                    diagnostics: diagnostics::parse(&stderr),
+                    stderr,
+                });
+                Ok(passes)
+            }
+            Some(Err(error)) => Err(process_error(2, error, passes)),
+        }
+    }
+}
+
+/// Compiles the problem unless its inputs haven't changed since the last successful build
+pub fn compile_cached(
+    backend: &dyn Backend,
+    problem: &problem::Problem,
+) -> Result<Vec<CompilationPass>, CompileProcessError> {
+    if let Some(passes) = cache::lookup(backend, problem) {
+        debug!("The sources haven't changed, skipping the compilation");
+        return Ok(passes);
+    }
+
+    let passes = backend.compile_problem(problem)?;
+    cache::store(backend, problem, &passes);
+    Ok(passes)
+}
+
+impl fmt::Debug for dyn Backend + '_ {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+pub fn find_backend(name: &str) -> Option<&'static dyn Backend> {
+    BACKENDS.iter().copied().find(|b| b.name() == name)
+}
+
+/// Returns None if the profile base isn't a built-in C or C++ compiler
+pub fn create_compiler(profile: Profile) -> Option<ProfileCompiler> {
+    ProfileCompiler::from_profile(profile)
+}
+
+pub fn backend_for_source(source: &path::Path) -> Option<&'static dyn Backend> {
+    let extension = source.extension()?.to_str()?;
+    BACKENDS
+        .iter()
+        .copied()
+        .find(|b| b.extensions().contains(&extension))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn find_backend_test() {
+        assert_eq!(find_backend("g++").unwrap().jutge_id(), "P1++");
+        assert_eq!(find_backend("clang++").unwrap().jutge_id(), "CLang++17");
+        assert_eq!(find_backend("ghc").unwrap().jutge_id(), "GHC");
+        assert_eq!(find_backend("javac").unwrap().jutge_id(), "JDK");
+        assert_eq!(find_backend("python3").unwrap().jutge_id(), "Python3");
+        assert!(find_backend("cobol").is_none());
+    }
+
+    #[test]
+    fn create_compiler_test() {
+        let profile = |base: &str| Profile {
+            name: String::from("mine"),
+            base: base.to_owned(),
+            command: None,
+            flags1: Some(vec![String::from("-std=c++17")]),
+            flags2: None,
+            libs: None,
+        };
+
+        let backend = create_compiler(profile("clang++")).unwrap();
+        assert_eq!(backend.name(), "mine");
+        assert_eq!(backend.jutge_id(), "CLang++17");
+        assert_eq!(backend.extensions(), &["cc", "cpp", "cxx"]);
+        assert!(create_compiler(profile("python3")).is_none());
+    }
+
+    #[test]
+    fn backend_for_source_test() {
+        let backend = |source: &str| backend_for_source(path::Path::new(source)).map(|b| b.name());
+        assert_eq!(backend("main.cc"), Some("g++"));
+        assert_eq!(backend("fast.cpp"), Some("g++"));
+        assert_eq!(backend("main.c"), Some("gcc"));
+        assert_eq!(backend("main.hs"), Some("ghc"));
+        assert_eq!(backend("main.java"), Some("javac"));
+        assert_eq!(backend("main.py"), Some("python3"));
+        assert_eq!(backend("main.txt"), None);
+        assert_eq!(backend("main"), None);
+    }
+}
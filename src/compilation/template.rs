@@ -1,3 +1,4 @@
+use crate::compilation::cache;
 use crate::{debug, problem};
 use std::io::Write;
 use std::{env, fmt, fs, io, path};
@@ -12,76 +13,150 @@ macro_rules! get_template {
     };
 }
 
-fn apply_normal_template(original: &str) -> String {
-    format!(
-        get_template!("normal.cc.in"),
-        original = original,
-        stub = get_template!("stub.cc.in")
-    )
+/// Code copied into a template, with the path of the file it comes from
+pub struct Source<'a> {
+    contents: &'a str,
+    file: String,
 }
 
-fn apply_nomain_template(original: &str, main: &str) -> String {
-    format!(
-        get_template!("nomain.cc.in"),
-        original = original,
-        stub = get_template!("stub.cc.in"),
-        main = main
-    )
+impl Source<'_> {
+    pub fn new<'a>(contents: &'a str, file: &path::Path) -> Source<'a> {
+        // The path is written inside a string literal of the #line directive
+        let file = file
+            .to_string_lossy()
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"");
+        Source { contents, file }
+    }
+}
+
+pub struct Templates {
+    normal: fn(&Source) -> String,
+    nomain: fn(&Source, &Source) -> String,
+}
+
+impl Templates {
+    pub fn fingerprint(&self, fingerprint: &mut cache::Fingerprint) {
+        let empty = Source::new("", path::Path::new(""));
+        fingerprint.add((self.normal)(&empty));
+        fingerprint.add((self.nomain)(&empty, &empty));
+    }
 }
 
+pub static CXX_TEMPLATES: Templates = Templates {
+    normal: |original| {
+        format!(
+            get_template!("normal.cc.in"),
+            original = original.contents,
+            original_file = original.file,
+            stub = get_template!("stub.cc.in")
+        )
+    },
+    nomain: |original, main| {
+        format!(
+            get_template!("nomain.cc.in"),
+            original = original.contents,
+            original_file = original.file,
+            stub = get_template!("stub.cc.in"),
+            main = main.contents,
+            main_file = main.file
+        )
+    },
+};
+
+pub static C_TEMPLATES: Templates = Templates {
+    normal: |original| {
+        format!(
+            get_template!("normal.c.in"),
+            original = original.contents,
+            original_file = original.file,
+            stub = get_template!("stub.c.in")
+        )
+    },
+    nomain: |original, main| {
+        format!(
+            get_template!("nomain.c.in"),
+            original = original.contents,
+            original_file = original.file,
+            stub = get_template!("stub.c.in"),
+            main = main.contents,
+            main_file = main.file
+        )
+    },
+};
+
+// Placeholder used in the templates to go back to the generated file after the copied code
+const RESUME_DIRECTIVE: &str = "#line resume";
+
 pub enum Error {
-    ErrorCreatingTmpFolder(io::Error),
-    ErrorCreatingFile(io::Error),
+    CantCreateTmpFolder(io::Error),
+    CantCreateFile(io::Error),
     CantReadSources(io::Error),
-    CantReadDownloadedMain(io::Error),
-    ErrorWritingFile(io::Error),
+    MissingDownloadedMain(io::Error),
+    CantWriteFile(io::Error),
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Error::ErrorCreatingTmpFolder(e) => {
+            Error::CantCreateTmpFolder(e) => {
                 write!(f, "Couldn't create a temporal folder: {}", e)
             }
-            Error::ErrorCreatingFile(e) => write!(f, "Couldn't create the file: {}", e),
+            Error::CantCreateFile(e) => write!(f, "Couldn't create the file: {}", e),
             Error::CantReadSources(e) => write!(f, "Couldn't read your sources: {}", e),
-            Error::CantReadDownloadedMain(e) => {
+            Error::MissingDownloadedMain(e) => {
                 write!(f, "Couldn't read the downloaded sources: {}", e)
             }
-            Error::ErrorWritingFile(e) => write!(f, "Unable to write the file: {}", e),
+            Error::CantWriteFile(e) => write!(f, "Unable to write the file: {}", e),
         }
     }
 }
 
-impl From<Error> for crate::Error {
-    fn from(e: Error) -> Self {
-        crate::Error {
-            description: format!("Couldn't generate a main.cc file to compile: {}", e),
-            exitcode: exitcode::DATAERR,
+/// Replaces the resume placeholders with #line directives pointing back to the generated file
+fn resume_line_directives(contents: &str, generated: &path::Path) -> String {
+    let generated = Source::new("", generated).file;
+    let mut resolved = String::with_capacity(contents.len());
+    for (i, line) in contents.split_inclusive('\n').enumerate() {
+        if line.trim_end() == RESUME_DIRECTIVE {
+            // The line after the directive is the line i + 2 of the generated file
+            resolved.push_str(&format!("#line {} \"{}\"", i + 2, generated));
+            resolved.push_str(&line[RESUME_DIRECTIVE.len()..]);
+        } else {
+            resolved.push_str(line);
         }
     }
+    resolved
 }
 
-pub fn generate_main(problem: &problem::Problem) -> Result<path::PathBuf, Error> {
-    let generated_main_path = problem.tmp_dir.join("main.cc");
+pub fn generate_main(
+    problem: &problem::Problem,
+    templates: &Templates,
+) -> Result<path::PathBuf, Error> {
+    let generated_main_path = problem
+        .tmp_dir
+        .join("main")
+        .with_extension(problem.backend.extensions()[0]);
 
     debug!("Creating {}...", generated_main_path.to_string_lossy());
     fs::create_dir_all(generated_main_path.parent().unwrap())
-        .map_err(Error::ErrorCreatingTmpFolder)?;
+        .map_err(Error::CantCreateTmpFolder)?;
     let mut generated_main =
-        fs::File::create(&generated_main_path).map_err(Error::ErrorCreatingFile)?;
+        fs::File::create(&generated_main_path).map_err(Error::CantCreateFile)?;
 
     debug!("Reading {}...", problem.source.to_string_lossy());
     let original = fs::read_to_string(problem.source.as_path()).map_err(Error::CantReadSources)?;
+    let original = Source::new(original.as_str(), problem.source.as_path());
 
     debug!("Generating contents...");
-    let generated_main_contents = if problem.has_main {
-        apply_normal_template(original.as_str())
+    let generated_main_contents = if problem.has_main != Some(false) {
+        (templates.normal)(&original)
     } else {
-        let main = fs::read_to_string(problem.work_dir.join("main.cc"))
-            .map_err(Error::CantReadDownloadedMain)?;
-        apply_nomain_template(original.as_str(), main.as_str())
+        let downloaded_main = problem.downloaded_main();
+        let main = fs::read_to_string(&downloaded_main).map_err(Error::MissingDownloadedMain)?;
+        (templates.nomain)(&original, &Source::new(main.as_str(), &downloaded_main))
     };
+    let generated_main_contents =
+        resume_line_directives(&generated_main_contents, &generated_main_path);
 
     debug!(
         "Writing contents to {}...",
@@ -89,7 +164,7 @@ pub fn generate_main(problem: &problem::Problem) -> Result<path::PathBuf, Error>
     );
     generated_main
         .write_all(generated_main_contents.as_ref())
-        .map_err(Error::ErrorWritingFile)?;
+        .map_err(Error::CantWriteFile)?;
     Ok(generated_main_path)
 }
 
@@ -97,34 +172,78 @@ pub fn generate_main(problem: &problem::Problem) -> Result<path::PathBuf, Error>
 mod test {
     use super::*;
 
-    #[test]
-    fn apply_normal_template_test() {
-        let original = "// I'M THE ORIGINAL ONE";
-        let expected_output = env::current_dir()
+    fn get_expected_output(file: &str) -> String {
+        let path = env::current_dir()
             .unwrap()
             .join("tests")
             .join("resources")
-            .join("normal.cc");
+            .join(file);
+        fs::read_to_string(path).unwrap()
+    }
+
+    fn apply_normal(templates: &Templates, extension: &str) -> String {
+        let original = Source::new(
+            "// I'M THE ORIGINAL ONE",
+            path::Path::new("/problem/main")
+                .with_extension(extension)
+                .as_path(),
+        );
+        resume_line_directives(
+            &(templates.normal)(&original),
+            path::Path::new("/tmp/main")
+                .with_extension(extension)
+                .as_path(),
+        )
+    }
+
+    fn apply_nomain(templates: &Templates, extension: &str) -> String {
+        let original = Source::new(
+            "// I'M THE ORIGINAL ONE",
+            path::Path::new("/problem/main")
+                .with_extension(extension)
+                .as_path(),
+        );
+        let main = Source::new(
+            "// I'M THE MAIN ONE",
+            path::Path::new("/problem/downloaded/main")
+                .with_extension(extension)
+                .as_path(),
+        );
+        resume_line_directives(
+            &(templates.nomain)(&original, &main),
+            path::Path::new("/tmp/main")
+                .with_extension(extension)
+                .as_path(),
+        )
+    }
 
+    #[test]
+    fn apply_normal_template_test() {
         assert_eq!(
-            apply_normal_template(original),
-            fs::read_to_string(expected_output).unwrap()
+            apply_normal(&CXX_TEMPLATES, "cc"),
+            get_expected_output("normal.cc")
+        );
+        assert_eq!(
+            apply_normal(&C_TEMPLATES, "c"),
+            get_expected_output("normal.c")
         );
     }
 
     #[test]
     fn apply_nomain_template_test() {
-        let original = "// I'M THE ORIGINAL ONE";
-        let main = "// I'M THE MAIN ONE";
-        let expected_output = env::current_dir()
-            .unwrap()
-            .join("tests")
-            .join("resources")
-            .join("nomain.cc");
-
         assert_eq!(
-            apply_nomain_template(original, main),
-            fs::read_to_string(expected_output).unwrap()
+            apply_nomain(&CXX_TEMPLATES, "cc"),
+            get_expected_output("nomain.cc")
+        );
+        assert_eq!(
+            apply_nomain(&C_TEMPLATES, "c"),
+            get_expected_output("nomain.c")
         );
     }
+
+    #[test]
+    fn line_directive_escaping_test() {
+        let source = Source::new("", path::Path::new("/my \"problems\"/a\\b.cc"));
+        assert_eq!(source.file, "/my \\\"problems\\\"/a\\\\b.cc");
+    }
 }
@@ -1,7 +1,10 @@
-use crate::{debug, fetch, ux};
+use crate::{cli, compilation, debug, fetch, testing, ux};
+use clap::Parser;
 use configparser::ini;
 use core::fmt;
-use std::{env, fs, io, path};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::{env, ffi, fs, io, path, thread, time};
 
 #[derive(Debug)]
 pub enum Error {
@@ -10,6 +13,9 @@ pub enum Error {
     CantCreateTmpDir(io::Error),
     UnknownProblemDir(io::Error),
     CantCreateConfigFile(io::Error),
+    CantReadConfigFile(path::PathBuf, String),
+    InvalidValue(String),
+    TestOnlyOption(&'static str),
 }
 
 impl fmt::Display for Error {
@@ -26,31 +32,104 @@ impl fmt::Display for Error {
             }
             Error::UnknownProblemDir(e) => write!(f, "Can't determine the problem dir: {}", e),
             Error::CantCreateConfigFile(e) => write!(f, "Can't create the config file: {}", e),
+            Error::CantReadConfigFile(path, e) => {
+                write!(f, "Can't read {}: {}", path.to_string_lossy(), e)
+            }
+            Error::InvalidValue(option) => write!(f, "Invalid value for the option {}", option),
+            Error::TestOnlyOption(option) => {
+                write!(
+                    f,
+                    "The option {} can only be used to test a problem",
+                    option
+                )
+            }
         }
     }
 }
 
 impl From<Error> for crate::Error {
     fn from(e: Error) -> Self {
+        let exitcode = match e {
+            Error::TestOnlyOption(_) => exitcode::USAGE,
+            _ => exitcode::IOERR,
+        };
+
         crate::Error {
             description: format!("Error preparing the program: {}", e),
-            exitcode: exitcode::IOERR,
+            exitcode,
+        }
+    }
+}
+
+/// Settings that can also be given per problem, in an advocat.ini file inside its folder
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct ProblemSettings {
+    pub source: Option<path::PathBuf>,
+    pub output: Option<path::PathBuf>,
+    pub compiler: Option<String>,
+}
+
+impl ProblemSettings {
+    fn load(section: &HashMap<String, Option<String>>) -> Result<ProblemSettings, Error> {
+        Ok(ProblemSettings {
+            source: get_value(section, "problem", "source")?,
+            output: get_value(section, "problem", "output")?,
+            compiler: get_value(section, "problem", "compiler")?,
+        })
+    }
+
+    fn merge(&mut self, other: ProblemSettings) {
+        if other.source.is_some() {
+            self.source = other.source;
+            // The default output name follows the newly given source
+            self.output = None;
+        }
+        if other.output.is_some() {
+            self.output = other.output;
+        }
+        if other.compiler.is_some() {
+            self.compiler = other.compiler;
         }
     }
 }
 
 #[derive(Clone)]
 pub struct Config {
+    pub command: cli::Command,
     pub log_level: ux::LogLevel,
+    pub output_format: ux::OutputFormat,
     pub problem_dir: path::PathBuf,
     pub config_dir: path::PathBuf,
     pub cache_dir: path::PathBuf,
     pub tmp_dir: path::PathBuf,
     pub credentials: Option<fetch::Credentials>,
+    pub limits: testing::Limits,
+    pub jobs: usize,
+    pub junit: Option<path::PathBuf>,
+    pub watch: bool,
+    pub sanitize: bool,
+    pub offline: bool,
+    pub auto_submit: bool,
+    pub problem_defaults: ProblemSettings,
+    pub problem_args: ProblemSettings,
+    pub compilers: Vec<compilation::ProfileCompiler>,
 }
 
 impl Config {
     pub fn generate() -> Result<Config, Error> {
+        Config::generate_from(env::args_os())
+    }
+
+    pub fn generate_from<I, T>(args: I) -> Result<Config, Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<ffi::OsString> + Clone,
+    {
+        let args = cli::Args::parse_from(args);
+        if let Some(option) = args.test_only_option() {
+            return Err(Error::TestOnlyOption(option));
+        }
+
         let dirs = directories::ProjectDirs::from("com", "rdvdev2", "advocat");
         let (config_dir, cache_dir) = if let Some(dirs) = dirs {
             (
@@ -66,12 +145,35 @@ impl Config {
         let problem_dir = env::current_dir().map_err(Error::UnknownProblemDir)?;
 
         let mut config = Config {
-            log_level: ux::LogLevel::Info,
+            command: args.command.unwrap_or(cli::Command::Test),
+            log_level: if args.debug {
+                ux::LogLevel::Debug
+            } else {
+                ux::LogLevel::Info
+            },
+            output_format: match args.format {
+                cli::Format::Human => ux::OutputFormat::Human,
+                cli::Format::Json => ux::OutputFormat::Json,
+            },
             problem_dir,
             config_dir,
             cache_dir,
             tmp_dir: env::temp_dir().join("advocat"),
             credentials: None,
+            limits: testing::Limits::default(),
+            jobs: thread::available_parallelism().map_or(1, |n| n.get()),
+            junit: args.junit,
+            watch: args.watch,
+            sanitize: args.sanitize,
+            offline: args.offline,
+            auto_submit: args.auto_submit,
+            problem_defaults: ProblemSettings::default(),
+            problem_args: ProblemSettings {
+                source: args.source,
+                output: args.output,
+                compiler: args.compiler,
+            },
+            compilers: Vec::new(),
         };
 
         debug!("Creating directories");
@@ -82,21 +184,60 @@ impl Config {
         debug!("Loading config file");
         config.load_config_file()?;
 
-        let mut args = env::args();
-        let _executable = args.next();
-        for arg in args {
-            match arg.as_str() {
-                "-d" | "--debug" => config.log_level = ux::LogLevel::Debug,
-                "-h" | "--help" => {} // TODO: Add a help message
-                _ => {}
-            }
+        if let Some(time_limit) = args.time_limit {
+            config.limits.time = parse_time_limit("--time-limit", time_limit)?;
+        }
+        if let Some(cpu_limit) = args.cpu_limit {
+            config.limits.cpu_time = parse_limit("--cpu-limit", cpu_limit)?;
+        }
+        if let Some(memory_limit) = args.memory_limit {
+            config.limits.memory = parse_limit("--memory-limit", memory_limit)?;
+        }
+        if let Some(jobs) = args.jobs {
+            config.jobs = parse_jobs("--jobs", jobs)?;
         }
 
         Ok(config)
     }
 
+    pub fn config_file_path(&self) -> path::PathBuf {
+        self.config_dir.join("config.ini")
+    }
+
+    pub fn problem_config_file_path(&self) -> path::PathBuf {
+        self.problem_dir.join("advocat.ini")
+    }
+
+    /// Merges the settings of the config file, the problem folder and the command line
+    pub fn problem_settings(&self) -> Result<ProblemSettings, Error> {
+        let mut settings = self.problem_defaults.clone();
+
+        let path = self.problem_config_file_path();
+        if path.is_file() {
+            debug!("Loading {}", path.to_string_lossy());
+            let problem_config = ini::Ini::new()
+                .load(path.as_path())
+                .map_err(|e| Error::CantReadConfigFile(path.clone(), e))?;
+            if let Some(problem) = problem_config.get("problem") {
+                settings.merge(ProblemSettings::load(problem)?);
+            }
+        }
+
+        settings.merge(self.problem_args.clone());
+        Ok(settings)
+    }
+
+    /// The profile names are case insensitive, as the config file parser lowercases the sections
+    pub fn find_compiler(&self, name: &str) -> Option<&dyn compilation::Backend> {
+        self.compilers
+            .iter()
+            .map(|c| c as &dyn compilation::Backend)
+            .find(|c| c.name() == name.to_lowercase())
+            .or_else(|| compilation::find_backend(name))
+    }
+
     fn load_config_file(&mut self) -> Result<(), Error> {
-        let config_file_path = self.config_dir.join("config.ini");
+        let config_file_path = self.config_file_path();
 
         if !config_file_path.is_file() {
             fs::File::create(config_file_path.as_path()).map_err(Error::CantCreateConfigFile)?;
@@ -116,8 +257,197 @@ impl Config {
                     ));
                 }
             }
+
+            if let Some(tests) = config_file.get("tests") {
+                if let Some(time_limit) = get_value(tests, "tests", "time_limit")? {
+                    self.limits.time = parse_time_limit("tests.time_limit", time_limit)?;
+                }
+                if let Some(cpu_limit) = get_value(tests, "tests", "cpu_limit")? {
+                    self.limits.cpu_time = parse_limit("tests.cpu_limit", cpu_limit)?;
+                }
+                if let Some(memory_limit) = get_value(tests, "tests", "memory_limit")? {
+                    self.limits.memory = parse_limit("tests.memory_limit", memory_limit)?;
+                }
+                if let Some(jobs) = get_value(tests, "tests", "jobs")? {
+                    self.jobs = parse_jobs("tests.jobs", jobs)?;
+                }
+            }
+
+            if let Some(fetch) = config_file.get("fetch") {
+                if let Some(offline) = get_value::<bool>(fetch, "fetch", "offline")? {
+                    // The --offline flag can't be overridden by the config file
+                    self.offline |= offline;
+                }
+            }
+
+            if let Some(submit) = config_file.get("submit") {
+                if let Some(auto) = get_value::<bool>(submit, "submit", "auto")? {
+                    self.auto_submit |= auto;
+                }
+            }
+
+            if let Some(problem) = config_file.get("problem") {
+                self.problem_defaults = ProblemSettings::load(problem)?;
+            }
+
+            self.load_compiler_profiles(&config_file)?;
         }
 
         Ok(())
     }
+
+    fn load_compiler_profiles(
+        &mut self,
+        config_file: &HashMap<String, HashMap<String, Option<String>>>,
+    ) -> Result<(), Error> {
+        for (section_name, section) in config_file {
+            if let Some(name) = section_name.strip_prefix("compiler.") {
+                debug!("Loading the compiler profile {}", name);
+                self.compilers
+                    .push(load_compiler_profile(name, section_name, section)?);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn get_value<T: FromStr>(
+    section: &HashMap<String, Option<String>>,
+    section_name: &str,
+    key: &str,
+) -> Result<Option<T>, Error> {
+    match section.get(key) {
+        Some(Some(value)) => value
+            .parse()
+            .map(Some)
+            .map_err(|_| Error::InvalidValue(format!("{}.{}", section_name, key))),
+        _ => Ok(None),
+    }
+}
+
+fn load_compiler_profile(
+    name: &str,
+    section_name: &str,
+    section: &HashMap<String, Option<String>>,
+) -> Result<compilation::ProfileCompiler, Error> {
+    let get_flags = |key| -> Result<Option<Vec<String>>, Error> {
+        let flags: Option<String> = get_value(section, section_name, key)?;
+        Ok(flags.map(|f| f.split_whitespace().map(String::from).collect()))
+    };
+
+    let profile = compilation::Profile {
+        name: name.to_owned(),
+        base: get_value(section, section_name, "base")?.unwrap_or_else(|| String::from("g++")),
+        command: get_value(section, section_name, "command")?,
+        flags1: get_flags("flags1")?,
+        flags2: get_flags("flags2")?,
+        libs: get_flags("libs")?,
+    };
+    compilation::create_compiler(profile)
+        .ok_or_else(|| Error::InvalidValue(format!("{}.base", section_name)))
+}
+
+fn parse_jobs(option: &str, jobs: usize) -> Result<usize, Error> {
+    if jobs > 0 {
+        Ok(jobs)
+    } else {
+        Err(Error::InvalidValue(option.to_owned()))
+    }
+}
+
+fn parse_limit(option: &str, limit: u64) -> Result<u64, Error> {
+    if limit > 0 {
+        Ok(limit)
+    } else {
+        Err(Error::InvalidValue(option.to_owned()))
+    }
+}
+
+fn parse_time_limit(option: &str, seconds: f64) -> Result<time::Duration, Error> {
+    if seconds.is_finite() && seconds > 0.0 {
+        Ok(time::Duration::from_secs_f64(seconds))
+    } else {
+        Err(Error::InvalidValue(option.to_owned()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::compilation::Backend;
+    use crate::{problem, test_utils};
+
+    fn get_section(values: &[(&str, &str)]) -> HashMap<String, Option<String>> {
+        values
+            .iter()
+            .map(|(k, v)| (k.to_string(), Some(v.to_string())))
+            .collect()
+    }
+
+    #[test]
+    fn merge_problem_settings_test() {
+        let mut settings = ProblemSettings {
+            source: Some(path::PathBuf::from("main.cc")),
+            output: Some(path::PathBuf::from("main.x")),
+            compiler: Some(String::from("g++")),
+        };
+        settings.merge(ProblemSettings {
+            source: Some(path::PathBuf::from("fast.cc")),
+            ..Default::default()
+        });
+        assert_eq!(
+            settings,
+            ProblemSettings {
+                source: Some(path::PathBuf::from("fast.cc")),
+                output: None,
+                compiler: Some(String::from("g++")),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_limit_test() {
+        assert_eq!(parse_limit("--cpu-limit", 2).unwrap(), 2);
+        assert!(matches!(
+            parse_limit("--memory-limit", 0),
+            Err(Error::InvalidValue(option)) if option == "--memory-limit"
+        ));
+    }
+
+    #[test]
+    fn test_only_option_test() {
+        assert!(matches!(
+            Config::generate_from(["advocat", "fetch", "--watch"]),
+            Err(Error::TestOnlyOption("--watch"))
+        ));
+    }
+
+    #[test]
+    fn load_compiler_profile_test() {
+        let section = get_section(&[("command", "g++-12"), ("flags1", "-std=c++17 -Wall")]);
+        let backend = load_compiler_profile("mine", "compiler.mine", &section).unwrap();
+        assert_eq!(backend.name(), "mine");
+        assert_eq!(backend.jutge_id(), "P1++");
+
+        let section = get_section(&[("base", "ghc")]);
+        assert!(matches!(
+            load_compiler_profile("mine", "compiler.mine", &section),
+            Err(Error::InvalidValue(_))
+        ));
+    }
+
+    #[test]
+    fn select_compiler_profile_test() {
+        let mut config = Config::generate_from(["advocat", "-c", "Mine"]).unwrap();
+        config.problem_dir = test_utils::get_tests_folder()
+            .join("problems")
+            .join("P00000_xx");
+        let config_file = ini::Ini::new()
+            .read(String::from("[compiler.Mine]\ncommand = g++\n"))
+            .unwrap();
+        config.load_compiler_profiles(&config_file).unwrap();
+
+        let problem = problem::Problem::new(&config).unwrap();
+        assert_eq!(problem.backend.name(), "mine");
+    }
 }
@@ -5,35 +5,55 @@ use std::io::Write;
 use std::{fmt, fs, io, path};
 
 pub enum Error {
-    CurlError(curl::Error),
-    IoError(io::Error),
-    AuthError,
+    Curl(curl::Error),
+    Io(io::Error),
+    Auth,
+    Status(u32),
+    NotAFile,
+    Offline(path::PathBuf),
+    Form(curl::FormError),
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Error::CurlError(e) => write!(f, "Connection error: {}", e),
-            Error::IoError(e) => write!(f, "IO error: {}", e),
-            Error::AuthError => write!(f, "The requested content isn't publicly available"),
+            Error::Curl(e) => write!(f, "Connection error: {}", e),
+            Error::Io(e) => write!(f, "IO error: {}", e),
+            Error::Auth => write!(f, "The requested content isn't publicly available"),
+            Error::Status(code) => write!(f, "The server answered with the HTTP status {}", code),
+            Error::NotAFile => write!(f, "The server answered with a web page instead of a file"),
+            Error::Offline(path) => write!(
+                f,
+                "Working offline, and {} hasn't been downloaded yet",
+                path.to_string_lossy()
+            ),
+            Error::Form(e) => write!(f, "Couldn't build the form to send: {}", e),
         }
     }
 }
 
 impl From<curl::Error> for Error {
     fn from(e: curl::Error) -> Self {
-        Self::CurlError(e)
+        Self::Curl(e)
+    }
+}
+
+impl From<curl::FormError> for Error {
+    fn from(e: curl::FormError) -> Self {
+        Self::Form(e)
     }
 }
 
 impl From<io::Error> for Error {
     fn from(e: io::Error) -> Self {
-        Self::IoError(e)
+        Self::Io(e)
     }
 }
 
 pub struct ConnectionManager {
     handle: easy::Easy,
+    credentials: Option<credentials::Credentials>,
+    authentication_attempted: bool,
 }
 
 impl ConnectionManager {
@@ -46,41 +66,147 @@ impl ConnectionManager {
         let mut handle = easy::Easy::new();
         handle.cookie_file(cookie_store.as_path())?;
         handle.cookie_jar(cookie_store.as_path())?;
-        let mut cm = ConnectionManager { handle };
 
-        if cm.check_is_authenticated()? {
-            debug!("Client is authenticated, reusing previous session")
-        } else if let Some(credentials) = &config.credentials {
+        // The session is only checked when some download requires it
+        Ok(ConnectionManager {
+            handle,
+            credentials: config.credentials.clone(),
+            authentication_attempted: false,
+        })
+    }
+
+    pub fn get_file(&mut self, url: &str, path: &path::Path) -> Result<(), Error> {
+        self.with_authentication(|cm| cm.download(url, path))
+    }
+
+    /// Downloads an HTML page, failing if jutge.org asks to sign in instead
+    pub fn get_page(&mut self, url: &str) -> Result<String, Error> {
+        self.with_authentication(|cm| {
+            let page = cm.fetch_page(url)?;
+            cm.check_status()?;
+            if is_login_page(&page) {
+                Err(Error::Auth)
+            } else {
+                Ok(page)
+            }
+        })
+    }
+
+    /// Sends a form, returning the URL it redirected to and its contents
+    pub fn post_form<F>(&mut self, url: &str, build_form: F) -> Result<(String, String), Error>
+    where
+        F: Fn() -> Result<easy::Form, Error>,
+    {
+        self.with_authentication(|cm| {
+            debug!("Sending a form to {}", url);
+            let mut response = Vec::new();
+
+            cm.handle.url(url)?;
+            cm.handle.httppost(build_form()?)?;
+            cm.handle.follow_location(true)?;
+            let transferred = {
+                let mut transfer = cm.handle.transfer();
+                transfer
+                    .write_function(|data| {
+                        response.extend_from_slice(data);
+                        Ok(data.len())
+                    })
+                    .and_then(|()| transfer.perform())
+            };
+            // The handle is shared with the other requests, so it's restored even if this one failed
+            cm.handle.follow_location(false)?;
+            transferred?;
+
+            let page = String::from_utf8_lossy(&response).to_string();
+            if is_login_page(&page) {
+                return Err(Error::Auth);
+            }
+            let location = cm.handle.effective_url()?.unwrap_or(url).to_owned();
+            Ok((location, page))
+        })
+    }
+
+    fn with_authentication<T, F>(&mut self, mut request: F) -> Result<T, Error>
+    where
+        F: FnMut(&mut Self) -> Result<T, Error>,
+    {
+        match request(self) {
+            Err(Error::Auth) if !self.authentication_attempted => {
+                debug!("The request requires authentication");
+                if self.authenticate()? {
+                    debug!("Retrying the request");
+                    request(self)
+                } else {
+                    Err(Error::Auth)
+                }
+            }
+            result => result,
+        }
+    }
+
+    /// Authenticates at most once per connection, returning whether the client ended up authenticated
+    fn authenticate(&mut self) -> Result<bool, Error> {
+        self.authentication_attempted = true;
+
+        if let Some(credentials) = self.credentials.clone() {
             debug!("Credentials were provided, authenticating");
-            if cm.try_to_authenticate(credentials)? {
+            if self.try_to_authenticate(&credentials)? {
                 debug!("Authentication was successful");
+                Ok(true)
             } else {
                 warning!("The provided jutge.org credentials are invalid!");
-            };
+                Ok(false)
+            }
         } else {
             debug!("No credentials available, running in unauthenticated mode");
+            Ok(false)
         }
-
-        Ok(cm)
     }
 
-    pub fn get_file(&mut self, url: &str, path: &path::Path) -> Result<(), Error> {
+    fn download(&mut self, url: &str, path: &path::Path) -> Result<(), Error> {
         debug!("Downloading {} to {}", url, path.to_string_lossy());
         let mut file = fs::File::create(path)?;
 
+        self.handle.get(true)?;
         self.handle.url(url)?;
         self.handle
             .write_function(move |data| file.write(data).or(Ok(0)))?;
         self.handle.perform()?;
 
-        if let Some(content_type) = self.handle.content_type()? {
-            if content_type.contains("html") {
+        let error = match self.check_status() {
+            Err(e) => Some(e),
+            // The file is an HTML page when jutge.org asks to sign in, or for unexpected errors
+            Ok(())
+                if self
+                    .handle
+                    .content_type()?
+                    .is_some_and(|t| t.contains("html")) =>
+            {
+                let page = fs::read(path)?;
+                if is_login_page(&String::from_utf8_lossy(&page)) {
+                    Some(Error::Auth)
+                } else {
+                    Some(Error::NotAFile)
+                }
+            }
+            Ok(()) => None,
+        };
+
+        match error {
+            Some(e) => {
                 fs::remove_file(path)?;
-                return Err(Error::AuthError);
+                Err(e)
             }
+            None => Ok(()),
         }
+    }
 
-        Ok(())
+    /// Fails if the last response has an error status, like 404 for a missing file
+    fn check_status(&mut self) -> Result<(), Error> {
+        match self.handle.response_code()? {
+            code if code >= 400 => Err(Error::Status(code)),
+            _ => Ok(()),
+        }
     }
 
     fn try_to_authenticate(
@@ -103,9 +229,15 @@ impl ConnectionManager {
     }
 
     fn check_is_authenticated(&mut self) -> Result<bool, Error> {
+        let dashboard = self.fetch_page("https://jutge.org/dashboard")?;
+        Ok(!is_login_page(&dashboard))
+    }
+
+    fn fetch_page(&mut self, url: &str) -> Result<String, Error> {
         let mut response = Vec::new();
 
-        self.handle.url("https://jutge.org/dashboard")?;
+        self.handle.get(true)?;
+        self.handle.url(url)?;
         {
             let mut transfer = self.handle.transfer();
             transfer.write_function(|data| {
@@ -115,6 +247,10 @@ impl ConnectionManager {
             transfer.perform()?;
         }
 
-        Ok(!String::from_utf8_lossy(&response).contains("Did you sign in?"))
+        Ok(String::from_utf8_lossy(&response).to_string())
     }
 }
+
+fn is_login_page(page: &str) -> bool {
+    page.contains("Did you sign in?")
+}
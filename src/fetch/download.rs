@@ -1,9 +1,43 @@
 use crate::fetch::{connection_manager, unzip};
 use crate::{debug, problem, ux};
+use std::{fs, path};
+
+fn task_result(
+    result: Result<(), connection_manager::Error>,
+) -> (ux::TaskStatus, Option<connection_manager::Error>) {
+    match result {
+        Ok(()) => (ux::TaskStatus::Done, None),
+        Err(e) => (ux::TaskStatus::Fail, Some(e)),
+    }
+}
+
+fn download(
+    connection: Option<&mut connection_manager::ConnectionManager>,
+    url: &str,
+    path: &path::Path,
+) -> (ux::TaskStatus, Option<connection_manager::Error>) {
+    task_result(match connection {
+        Some(connection) => connection.get_file(url, path),
+        None => Err(connection_manager::Error::Offline(path.to_path_buf())),
+    })
+}
+
+fn download_page(
+    connection: Option<&mut connection_manager::ConnectionManager>,
+    url: &str,
+    path: &path::Path,
+) -> (ux::TaskStatus, Option<connection_manager::Error>) {
+    task_result(match connection {
+        Some(connection) => connection
+            .get_page(url)
+            .and_then(|page| fs::write(path, page).map_err(connection_manager::Error::from)),
+        None => Err(connection_manager::Error::Offline(path.to_path_buf())),
+    })
+}
 
 pub fn download_problem_zip(
     problem: &problem::Problem,
-    connection: &mut connection_manager::ConnectionManager,
+    connection: Option<&mut connection_manager::ConnectionManager>,
 ) -> (ux::TaskStatus, Option<connection_manager::Error>) {
     let path = problem.work_dir.join("problem.zip");
 
@@ -14,33 +48,71 @@ pub fn download_problem_zip(
         debug!("The download path is a folder");
         (ux::TaskStatus::SkipBad, None)
     } else {
-        match connection.get_file(&problem.zip_url, &path) {
-            Ok(()) => (ux::TaskStatus::Done, None),
-            Err(e) => (ux::TaskStatus::Fail, Some(e)),
-        }
+        download(connection, &problem.zip_url, &path)
     }
 }
 
 pub fn download_problem_main(
     problem: &problem::Problem,
-    connection: &mut connection_manager::ConnectionManager,
+    connection: Option<&mut connection_manager::ConnectionManager>,
 ) -> (ux::TaskStatus, Option<connection_manager::Error>) {
-    let path = problem.work_dir.join("main.cc");
+    let path = problem.downloaded_main();
 
-    if problem.has_main || path.is_file() {
-        debug!("Problem main.cc already downloaded or unnecessary");
+    // Without reading the source, the main() is downloaded whenever the backend may need it
+    let unnecessary = problem.has_main == Some(true) || !problem.backend.allows_nomain();
+    if unnecessary || path.is_file() {
+        debug!("Problem main() already downloaded or unnecessary");
         (ux::TaskStatus::SkipGood, None)
     } else if path.is_dir() {
         debug!("The download path is a folder");
         (ux::TaskStatus::SkipBad, None)
     } else {
-        match connection.get_file(&problem.main_cc_url, &path) {
-            Ok(()) => (ux::TaskStatus::Done, None),
-            Err(e) => (ux::TaskStatus::Fail, Some(e)),
-        }
+        download(connection, &problem.main_url, &path)
+    }
+}
+
+pub fn download_statement_pdf(
+    problem: &problem::Problem,
+    connection: Option<&mut connection_manager::ConnectionManager>,
+) -> (ux::TaskStatus, Option<connection_manager::Error>) {
+    let path = problem.work_dir.join("statement.pdf");
+
+    if path.is_file() {
+        debug!("Problem statement already downloaded");
+        (ux::TaskStatus::SkipGood, None)
+    } else if path.is_dir() {
+        debug!("The download path is a folder");
+        (ux::TaskStatus::SkipBad, None)
+    } else {
+        download(connection, &problem.pdf_url, &path)
     }
 }
 
+pub fn download_statement_html(
+    problem: &problem::Problem,
+    connection: Option<&mut connection_manager::ConnectionManager>,
+) -> (ux::TaskStatus, Option<connection_manager::Error>) {
+    let path = problem.work_dir.join("statement.html");
+
+    if path.is_file() {
+        debug!("Problem statement already downloaded");
+        (ux::TaskStatus::SkipGood, None)
+    } else if path.is_dir() {
+        debug!("The download path is a folder");
+        (ux::TaskStatus::SkipBad, None)
+    } else {
+        download_page(connection, &problem.html_url, &path)
+    }
+}
+
+pub fn download_submission_source(
+    connection: &mut connection_manager::ConnectionManager,
+    url: &str,
+    path: &path::Path,
+) -> (ux::TaskStatus, Option<connection_manager::Error>) {
+    download(Some(connection), url, path)
+}
+
 pub fn unzip_problem_tests(problem: &problem::Problem) -> (ux::TaskStatus, Option<unzip::Error>) {
     let zip_path = problem.work_dir.join("problem.zip");
     let tests_path = problem.work_dir.join("samples");
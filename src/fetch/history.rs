@@ -0,0 +1,159 @@
+use crate::compilation;
+use crate::fetch::submit;
+use regex::Regex;
+use serde::Serialize;
+
+#[derive(Serialize, PartialEq, Debug)]
+pub struct PastSubmission {
+    pub id: String,
+    pub date: Option<String>,
+    pub compiler: Option<String>,
+    pub verdict: Option<String>,
+}
+
+/// Id that jutge.org gives to the submission number N
+pub fn submission_id(number: u32) -> String {
+    format!("S{:03}", number)
+}
+
+fn submission_number(id: &str) -> u32 {
+    id.trim_start_matches('S').parse().unwrap_or_default()
+}
+
+/// Extension of the code written for a jutge.org compiler, if it's a known one
+pub fn source_extension(compiler: &str) -> Option<&'static str> {
+    // Besides the ones advocat uses, jutge.org has other versions of each language (G++11, Python3.10...)
+    const FAMILIES: [(&str, &str); 11] = [
+        ("p1++", "cc"),
+        ("g++", "cc"),
+        ("clang++", "cc"),
+        ("c++", "cc"),
+        ("gcc", "c"),
+        ("clang", "c"),
+        ("python", "py"),
+        ("ghc", "hs"),
+        ("haskell", "hs"),
+        ("jdk", "java"),
+        ("java", "java"),
+    ];
+
+    if let Some(backend) = compilation::BACKENDS
+        .iter()
+        .find(|b| b.jutge_id() == compiler)
+    {
+        return Some(backend.extensions()[0]);
+    }
+    let compiler = compiler.to_lowercase();
+    FAMILIES
+        .iter()
+        .find(|(prefix, _)| compiler.starts_with(prefix))
+        .map(|(_, extension)| *extension)
+}
+
+/// Parses the table of the submissions page of a problem
+pub fn parse_submissions(page: &str) -> Vec<PastSubmission> {
+    let rows = Regex::new(r"(?is)<tr\b.*?</tr>").unwrap();
+    let cells = Regex::new(r"(?is)<td\b[^>]*>(.*?)</td>").unwrap();
+    let tags = Regex::new(r"(?s)<[^>]*>").unwrap();
+    let id = Regex::new(r"/submissions/(S\d+)").unwrap();
+    let date = Regex::new(r"\d{4}-\d{2}-\d{2}[ T]\d{2}:\d{2}(:\d{2})?").unwrap();
+
+    let mut submissions: Vec<PastSubmission> = Vec::new();
+    for row in rows.find_iter(page).map(|r| r.as_str()) {
+        let id = match id.captures(row) {
+            Some(captures) => captures[1].to_owned(),
+            None => continue,
+        };
+        if submissions.iter().any(|s| s.id == id) {
+            continue;
+        }
+
+        let texts: Vec<String> = cells
+            .captures_iter(row)
+            .map(|c| tags.replace_all(&c[1], "").trim().to_owned())
+            .collect();
+        submissions.push(PastSubmission {
+            date: date.find(row).map(|d| d.as_str().to_owned()),
+            // The only cell left once the id, the date and the verdict icon are skipped
+            compiler: texts
+                .into_iter()
+                .find(|t| !t.is_empty() && *t != id && !date.is_match(t)),
+            verdict: submit::parse_verdict(row),
+            id,
+        });
+    }
+
+    submissions.sort_by_key(|s| submission_number(&s.id));
+    submissions
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_submissions_test() {
+        let page = "<table>
+<tr><th>Submission</th><th>Date</th><th>Compiler</th><th>Verdict</th></tr>
+<tr>
+  <td><a href='/problems/P00000_xx/submissions/S002'>S002</a></td>
+  <td>2023-03-02 10:15:00</td>
+  <td><b>P1++</b></td>
+  <td><img src='/ico/veredicts/AC.png'></td>
+</tr>
+<tr>
+  <td><a href='/problems/P00000_xx/submissions/S001'>S001</a></td>
+  <td>2023-03-01 18:00:31</td>
+  <td>Python3</td>
+  <td><img src='/ico/veredicts/WA.png'></td>
+</tr>
+<tr>
+  <td><a href='/problems/P00000_xx/submissions/S1000'>S1000</a></td>
+  <td>2023-04-01 09:00:00</td>
+  <td>G++11</td>
+  <td><img src='/ico/veredicts/pending.png'></td>
+</tr>
+</table>";
+
+        assert_eq!(
+            parse_submissions(page),
+            vec![
+                PastSubmission {
+                    id: String::from("S001"),
+                    date: Some(String::from("2023-03-01 18:00:31")),
+                    compiler: Some(String::from("Python3")),
+                    verdict: Some(String::from("WA")),
+                },
+                PastSubmission {
+                    id: String::from("S002"),
+                    date: Some(String::from("2023-03-02 10:15:00")),
+                    compiler: Some(String::from("P1++")),
+                    verdict: Some(String::from("AC")),
+                },
+                PastSubmission {
+                    id: String::from("S1000"),
+                    date: Some(String::from("2023-04-01 09:00:00")),
+                    compiler: Some(String::from("G++11")),
+                    verdict: None,
+                },
+            ]
+        );
+        assert!(parse_submissions("<p>No submissions</p>").is_empty());
+    }
+
+    #[test]
+    fn source_extension_test() {
+        assert_eq!(source_extension("P1++"), Some("cc"));
+        assert_eq!(source_extension("G++17"), Some("cc"));
+        assert_eq!(source_extension("Clang"), Some("c"));
+        assert_eq!(source_extension("GCC"), Some("c"));
+        assert_eq!(source_extension("Python3.10"), Some("py"));
+        assert_eq!(source_extension("Brainfuck"), None);
+    }
+
+    #[test]
+    fn submission_id_test() {
+        assert_eq!(submission_id(3), "S003");
+        assert_eq!(submission_id(1234), "S1234");
+    }
+}
@@ -1,42 +1,80 @@
-use crate::{config, error, problem, ux, warning};
-use std::fmt;
+use crate::{config, debug, error, problem, report, ux, warning};
+use std::{fmt, path};
 
 mod connection_manager;
 mod credentials;
 mod download;
+mod history;
+mod submit;
 mod unzip;
 
 pub use credentials::Credentials;
+pub use history::PastSubmission;
 
-pub fn fetch_resources(
-    problem: &problem::Problem,
+pub struct Resources {
+    pub tests: bool,
+    pub tasks: Vec<report::Task>,
+}
+
+pub struct Statement {
+    pub html: bool,
+    pub tasks: Vec<report::Task>,
+}
+
+fn connect(
     config: &config::Config,
-) -> Result<(bool, bool, bool), crate::Error> {
-    let mut connection =
-        connection_manager::ConnectionManager::new(config).map_err(|e| crate::Error {
+) -> Result<Option<connection_manager::ConnectionManager>, crate::Error> {
+    if config.offline {
+        debug!("Working offline, only the cached files will be used");
+        return Ok(None);
+    }
+
+    connection_manager::ConnectionManager::new(config)
+        .map(Some)
+        .map_err(|e| crate::Error {
             description: format!("Couldn't start the connection manager: {}", e),
             exitcode: exitcode::IOERR,
-        })?;
+        })
+}
+
+pub fn fetch_resources(
+    problem: &problem::Problem,
+    config: &config::Config,
+) -> Result<Resources, crate::Error> {
+    let mut connection = connect(config)?;
 
     let zip = execute_task("Downloading problem zip", || {
-        download::download_problem_zip(problem, &mut connection)
+        download::download_problem_zip(problem, connection.as_mut())
     });
-    let main_cc = execute_task("Downloading problem main.cc", || {
-        download::download_problem_main(problem, &mut connection)
+    let main_cc = execute_task("Downloading problem main()", || {
+        download::download_problem_main(problem, connection.as_mut())
     });
     let tests = execute_task("Extracting tests", || {
         download::unzip_problem_tests(problem)
     });
+    let (zip, main_cc, tests, tasks) = (
+        zip.status.is_ok(),
+        main_cc.status.is_ok(),
+        tests.status.is_ok(),
+        vec![zip, main_cc, tests],
+    );
 
     if !zip {
         warning!("Unable to retrieve tests!");
     }
 
-    if !main_cc {
+    if !main_cc && problem.has_main.is_none() {
+        // Most problems don't provide a main(), so it's only an issue for those that do
+        warning!("Unable to retrieve the main() of the problem, if it has one");
+    } else if !main_cc {
+        let mut description = String::from(
+            "Unable to retrieve the main() of the problem, which is required to compile your binary!",
+        );
+        if config.offline {
+            description.push_str(" Run `advocat fetch` without --offline to download it.");
+        }
         return Err(crate::Error {
-            description: String::from(
-                "Unable to retrieve the main.cc file, which is required to compile your binary!",
-            ),
+            description,
             exitcode: exitcode::IOERR,
         });
     }
@@ -45,10 +83,199 @@ pub fn fetch_resources(
         warning!("Unable to unzip tests!");
     }
 
-    Ok((zip, main_cc, tests))
+    Ok(Resources { tests, tasks })
+}
+
+pub fn fetch_statement(
+    problem: &problem::Problem,
+    config: &config::Config,
+) -> Result<Statement, crate::Error> {
+    let mut connection = connect(config)?;
+
+    let pdf = execute_task("Downloading statement (PDF)", || {
+        download::download_statement_pdf(problem, connection.as_mut())
+    });
+    let html = execute_task("Downloading statement (HTML)", || {
+        download::download_statement_html(problem, connection.as_mut())
+    });
+
+    if !pdf.status.is_ok() && !html.status.is_ok() {
+        return Err(crate::Error {
+            description: String::from("Unable to retrieve the statement of the problem!"),
+            exitcode: exitcode::IOERR,
+        });
+    }
+
+    Ok(Statement {
+        html: html.status.is_ok(),
+        tasks: vec![pdf, html],
+    })
+}
+
+/// Connects to jutge.org for the actions that can't work offline
+fn connect_online(
+    config: &config::Config,
+    action: &str,
+) -> Result<connection_manager::ConnectionManager, crate::Error> {
+    connect(config)?.ok_or_else(|| crate::Error {
+        description: format!("Can't {} while working offline!", action),
+        exitcode: exitcode::UNAVAILABLE,
+    })
+}
+
+pub fn submit_solution(
+    problem: &problem::Problem,
+    config: &config::Config,
+) -> Result<report::Submission, crate::Error> {
+    const UPLOAD: &str = "Uploading your solution";
+    const VERDICT: &str = "jutge.org verdict";
+
+    let mut connection = connect_online(config, "submit your solution")?;
+
+    ux::show_task_status(UPLOAD, ux::TaskType::Fetch, &ux::TaskStatus::InProgress);
+    let submission_url = connection
+        .post_form(&problem.submit_url, || submit::build_form(problem))
+        .map_err(|e| format!("Couldn't submit your solution: {}", e))
+        .and_then(|(location, page)| {
+            submit::find_submission_url(problem, &location, &page)
+                .ok_or_else(|| String::from("jutge.org didn't accept your submission"))
+        });
+    let submission_url = match submission_url {
+        Ok(submission_url) => {
+            ux::show_task_status(UPLOAD, ux::TaskType::Fetch, &ux::TaskStatus::Done);
+            submission_url
+        }
+        Err(description) => {
+            ux::show_task_status(UPLOAD, ux::TaskType::Fetch, &ux::TaskStatus::Fail);
+            return Err(crate::Error {
+                description,
+                exitcode: exitcode::UNAVAILABLE,
+            });
+        }
+    };
+    debug!("Submission available at {}", submission_url);
+
+    ux::show_task_status(VERDICT, ux::TaskType::Test, &ux::TaskStatus::InProgress);
+    let verdict = submit::wait_for_verdict(&mut connection, &submission_url).map_err(|e| {
+        ux::show_task_status(VERDICT, ux::TaskType::Test, &ux::TaskStatus::Fail);
+        crate::Error {
+            description: format!("Couldn't get the verdict of your submission: {}", e),
+            exitcode: exitcode::UNAVAILABLE,
+        }
+    })?;
+
+    let status = match &verdict {
+        Some(code) => submit::verdict_status(code),
+        None => ux::TaskStatus::SkipBad,
+    };
+    ux::show_task_status(VERDICT, ux::TaskType::Test, &status);
+
+    Ok(report::Submission {
+        url: submission_url,
+        status,
+        description: verdict
+            .as_deref()
+            .map(|code| submit::verdict_description(code).to_owned()),
+        verdict,
+    })
+}
+
+pub fn fetch_history(
+    problem: &problem::Problem,
+    config: &config::Config,
+) -> Result<Vec<PastSubmission>, crate::Error> {
+    let mut connection = connect_online(config, "get your submissions")?;
+    download_history(problem, &mut connection)
+}
+
+fn download_history(
+    problem: &problem::Problem,
+    connection: &mut connection_manager::ConnectionManager,
+) -> Result<Vec<PastSubmission>, crate::Error> {
+    const TASK: &str = "Downloading your submissions";
+
+    ux::show_task_status(TASK, ux::TaskType::Fetch, &ux::TaskStatus::InProgress);
+    match connection.get_page(&problem.submit_url) {
+        Ok(page) => {
+            ux::show_task_status(TASK, ux::TaskType::Fetch, &ux::TaskStatus::Done);
+            Ok(history::parse_submissions(&page))
+        }
+        Err(e) => {
+            ux::show_task_status(TASK, ux::TaskType::Fetch, &ux::TaskStatus::Fail);
+            Err(crate::Error {
+                description: format!("Couldn't get your submissions: {}", e),
+                exitcode: exitcode::UNAVAILABLE,
+            })
+        }
+    }
+}
+
+/// Downloads the code of a previous submission next to the problem source, returning its path
+pub fn fetch_submission_source(
+    problem: &problem::Problem,
+    config: &config::Config,
+    number: u32,
+) -> Result<path::PathBuf, crate::Error> {
+    let mut connection = connect_online(config, "download your submissions")?;
+
+    let id = history::submission_id(number);
+    let submissions = download_history(problem, &mut connection)?;
+    let submission = submissions
+        .iter()
+        .find(|s| s.id == id)
+        .ok_or_else(|| crate::Error {
+            description: format!("There isn't any submission {} for {}", id, problem.id),
+            exitcode: exitcode::DATAERR,
+        })?;
+
+    // The submission may have been written in another language
+    let extension = match submission
+        .compiler
+        .as_deref()
+        .and_then(history::source_extension)
+    {
+        Some(extension) => extension,
+        None => {
+            let extension = problem.backend.extensions()[0];
+            warning!(
+                "Unknown compiler for {}, saving its code with the .{} extension",
+                id,
+                extension
+            );
+            extension
+        }
+    };
+    let path = problem.source.with_file_name(format!(
+        "{}.{}.{}",
+        problem
+            .source
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy(),
+        id,
+        extension
+    ));
+    if path.exists() {
+        return Err(crate::Error {
+            description: format!("{} already exists", path.to_string_lossy()),
+            exitcode: exitcode::CANTCREAT,
+        });
+    }
+
+    let url = format!("{}/{}/code", problem.submit_url, id);
+    let task = execute_task(&format!("Downloading {}", id), || {
+        download::download_submission_source(&mut connection, &url, &path)
+    });
+    if !task.status.is_ok() {
+        return Err(crate::Error {
+            description: format!("Couldn't download the code of {}", id),
+            exitcode: exitcode::UNAVAILABLE,
+        });
+    }
+    Ok(path)
 }
 
-fn execute_task<T, E: fmt::Display + Sized>(name: &str, mut task: T) -> bool
+fn execute_task<T, E: fmt::Display + Sized>(name: &str, mut task: T) -> report::Task
 where
     T: FnMut() -> (ux::TaskStatus, Option<E>),
 {
@@ -56,8 +283,13 @@ where
     let (status, err) = task();
 
     ux::show_task_status(name, ux::TaskType::Fetch, &status);
-    if let Some(err) = err {
+    if let Some(err) = &err {
         error!("The task [{}] returned the following error: {}", name, err);
     }
-    status.is_ok()
+
+    report::Task {
+        name: name.to_owned(),
+        status,
+        error: err.map(|e| e.to_string()),
+    }
 }
@@ -0,0 +1,186 @@
+use crate::fetch::connection_manager;
+use crate::{debug, problem, ux};
+use curl::easy;
+use regex::Regex;
+use std::{thread, time};
+
+const POLL_INTERVAL: time::Duration = time::Duration::from_secs(3);
+const MAX_POLLS: u32 = 60;
+
+pub fn build_form(problem: &problem::Problem) -> Result<easy::Form, connection_manager::Error> {
+    let mut form = easy::Form::new();
+    form.part("compiler_id")
+        .contents(problem.backend.jutge_id().as_bytes())
+        .add()?;
+    form.part("annotation")
+        .contents(format!("Submitted with advocat v{}", env!("CARGO_PKG_VERSION")).as_bytes())
+        .add()?;
+    form.part("file").file(&problem.source).add()?;
+    form.part("submit").contents(b"").add()?;
+    Ok(form)
+}
+
+/// Looks for the submission id in the page jutge.org redirected to after submitting
+pub fn find_submission_url(
+    problem: &problem::Problem,
+    location: &str,
+    page: &str,
+) -> Option<String> {
+    let re = Regex::new(&format!(
+        r"/problems/{}/submissions/(S\d+)",
+        regex::escape(&problem.id)
+    ))
+    .unwrap();
+    let id = re
+        .captures(location)
+        .or_else(|| re.captures(page))
+        .map(|c| c[1].to_owned())?;
+    Some(format!("{}/{}", problem.submit_url, id))
+}
+
+/// Returns the code of the first verdict icon in the HTML, or None if it's still pending
+pub fn parse_verdict(html: &str) -> Option<String> {
+    let re = Regex::new(r"(?i)\b(?:veredicts?|verdicts?)/(?P<code>[a-z]+)\.(?:png|svg)").unwrap();
+    let code = re.captures(html)?["code"].to_uppercase();
+    if code == "PENDING" {
+        None
+    } else {
+        Some(code)
+    }
+}
+
+/// Returns the verdict of a submission page, skipping the icons of any other submission listed
+/// before it
+pub fn parse_submission_verdict(page: &str, submission_url: &str) -> Option<String> {
+    let id = submission_url.rsplit('/').next().unwrap_or_default();
+    let re = Regex::new(&format!(r"\b{}\b", regex::escape(id))).unwrap();
+    let start = re.find(page).map_or(0, |m| m.start());
+    parse_verdict(&page[start..])
+}
+
+pub fn wait_for_verdict(
+    connection: &mut connection_manager::ConnectionManager,
+    submission_url: &str,
+) -> Result<Option<String>, connection_manager::Error> {
+    for _ in 0..MAX_POLLS {
+        thread::sleep(POLL_INTERVAL);
+        let page = connection.get_page(submission_url)?;
+        if let Some(verdict) = parse_submission_verdict(&page, submission_url) {
+            return Ok(Some(verdict));
+        }
+        debug!("The submission is still pending");
+    }
+    Ok(None)
+}
+
+pub fn verdict_description(code: &str) -> &'static str {
+    match code {
+        "AC" => "Accepted",
+        "WA" => "Wrong answer",
+        "PE" => "Presentation error",
+        "CE" => "Compilation error",
+        "EE" => "Execution error",
+        "TLE" => "Time limit exceeded",
+        "MLE" => "Memory limit exceeded",
+        "OLE" => "Output limit exceeded",
+        "IC" => "Invalid compiler",
+        "SE" => "Setter error",
+        "IE" => "Internal error",
+        _ => "Unknown verdict",
+    }
+}
+
+pub fn verdict_status(code: &str) -> ux::TaskStatus {
+    match code {
+        "AC" => ux::TaskStatus::Pass,
+        "TLE" => ux::TaskStatus::TimeLimitExceeded,
+        "MLE" => ux::TaskStatus::MemoryLimitExceeded,
+        "EE" => ux::TaskStatus::ExecutionError,
+        _ => ux::TaskStatus::Fail,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils;
+
+    #[test]
+    fn find_submission_url_test() {
+        let problem = test_utils::get_problem("P00000_xx");
+        assert_eq!(
+            find_submission_url(
+                &problem,
+                "https://jutge.org/problems/P00000_xx/submissions/S004",
+                ""
+            ),
+            Some(String::from(
+                "https://jutge.org/problems/P00000_xx/submissions/S004"
+            ))
+        );
+        assert_eq!(
+            find_submission_url(
+                &problem,
+                "https://jutge.org/problems/P00000_xx/submissions",
+                "<a href='/problems/P00000_xx/submissions/S012'>S012</a>"
+            ),
+            Some(String::from(
+                "https://jutge.org/problems/P00000_xx/submissions/S012"
+            ))
+        );
+        assert_eq!(
+            find_submission_url(
+                &problem,
+                "https://jutge.org/problems/P00000_xx",
+                "<a href='/problems/P99999_xx/submissions/S001'>S001</a>"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn parse_verdict_test() {
+        assert_eq!(
+            parse_verdict("<img src='/ico/veredicts/pending.png'>"),
+            None
+        );
+        assert_eq!(
+            parse_verdict("<img src='/ico/veredicts/AC.png' alt='AC'>"),
+            Some(String::from("AC"))
+        );
+        assert_eq!(
+            parse_verdict("<img src=\"/img/verdicts/tle.svg\">"),
+            Some(String::from("TLE"))
+        );
+        assert_eq!(parse_verdict("<p>Nothing here</p>"), None);
+        assert_eq!(
+            parse_verdict(
+                "<img src='/ico/veredicts/pending.png'> <img src='/ico/veredicts/AC.png'>"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn parse_submission_verdict_test() {
+        let url = "https://jutge.org/problems/P00000_xx/submissions/S004";
+        let page = |verdict: &str| {
+            format!(
+                "<ul>
+  <li><a href='/problems/P00000_xx/submissions/S0040'>S0040</a> <img src='/ico/veredicts/AC.png'></li>
+  <li><a href='/problems/P00000_xx/submissions/S003'>S003</a> <img src='/ico/veredicts/WA.png'></li>
+</ul>
+<h2>Submission S004</h2>
+<p>Verdict: <img src='/ico/veredicts/{}.png'></p>
+<p>Test cases: <img src='/ico/veredicts/AC.png'></p>",
+                verdict
+            )
+        };
+
+        assert_eq!(parse_submission_verdict(&page("pending"), url), None);
+        assert_eq!(
+            parse_submission_verdict(&page("WA"), url),
+            Some(String::from("WA"))
+        );
+    }
+}
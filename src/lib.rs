@@ -1,13 +1,18 @@
 use crate::problem::Problem;
-use std::{env, fmt, ops, path};
-use termion::{color, style};
+use std::{env, fmt, fs, io, ops, path};
+use termion::{clear, color, cursor, style};
 
+mod cache;
+mod cli;
 mod compilation;
 mod config;
 mod fetch;
 mod problem;
+mod report;
+mod statement;
 mod testing;
 pub mod ux;
+mod watch;
 
 #[cfg(test)]
 mod test_utils;
@@ -34,6 +39,7 @@ impl ops::Deref for Error {
 pub fn run() -> Result<exitcode::ExitCode, Error> {
     let config = config::Config::generate()?;
     ux::set_global_log_level(config.log_level);
+    ux::set_global_output_format(config.output_format);
 
     info!(
         "{} v{} by {}",
@@ -43,29 +49,555 @@ pub fn run() -> Result<exitcode::ExitCode, Error> {
     );
     debug!("Debug mode ON: Consider decreasing the log level to get quieter output.");
 
+    match &config.command {
+        cli::Command::Test if config.watch => watch_problem(&config),
+        cli::Command::Test => test_problem(&config),
+        cli::Command::Fetch => fetch_problem(&config),
+        cli::Command::Batch { dir } => batch_problems(&config, dir.as_deref()),
+        cli::Command::Clean => clean_problem(&config),
+        cli::Command::Config => show_config(&config),
+        cli::Command::Submit => submit_problem(&config),
+        cli::Command::History { get } => show_history(&config, *get),
+        cli::Command::Statement { text } => show_statement(&config, *text),
+        cli::Command::Cache { action } => manage_cache(&config, action),
+    }
+}
+
+fn load_problem(config: &config::Config) -> Result<Problem<'_>, Error> {
     debug!("Generating problem details...");
-    let problem = Problem::new(&config)?;
+    let problem = Problem::new(config)?;
     debug!("Done! Problem details: {:?}", problem);
 
-    let (_zip, _main_cc, tests) = fetch::fetch_resources(&problem, &config)?;
+    Ok(problem)
+}
+
+fn test_problem(config: &config::Config) -> Result<exitcode::ExitCode, Error> {
+    let (mut code, mut report) = check_problem(config)?;
+
+    let ready =
+        report.veredict.as_ref().map(|v| v.status) == Some(report::VeredictStatus::ReadyToSubmit);
+    if config.auto_submit && ready {
+        match offer_submission(config) {
+            Ok(Some(submission)) => {
+                code = show_submission(&submission);
+                report.submission = Some(submission);
+            }
+            Ok(None) => {}
+            // The test results are still reported when the submission fails
+            Err(e) => {
+                error!("{}", e);
+                code = *e;
+                report.error = Some(e.to_string());
+            }
+        }
+    }
+    let reports = [report];
+
+    if let Some(junit) = &config.junit {
+        write_junit(&reports, junit)?;
+    }
 
+    if !ux::is_human_output() {
+        report::print_json(&reports[0]);
+    }
+    Ok(code)
+}
+
+fn check_problem(config: &config::Config) -> Result<(exitcode::ExitCode, report::Report), Error> {
+    let problem = load_problem(config)?;
+    let mut report = report::Report::new(&problem.id);
+
+    let resources = fetch::fetch_resources(&problem, config)?;
+    report.fetch = resources.tasks;
+
+    let code = build_and_test(&problem, resources.tests, config, &mut report)?;
+    Ok((code, report))
+}
+
+fn build_and_test(
+    problem: &Problem,
+    jutge_tests: bool,
+    config: &config::Config,
+    report: &mut report::Report,
+) -> Result<exitcode::ExitCode, Error> {
     let tests = [
         load_tests(
             "jutge.org",
             problem.work_dir.join("samples").as_path(),
-            !tests,
+            !jutge_tests,
         ),
         load_tests("user", problem.source.parent().unwrap(), false),
     ];
 
-    debug!("Generating sources...");
-    let generated_sources = compilation::generate_main(&problem)?;
+    if ux::is_human_output() {
+        println!();
+    }
+    let compilation = execute_compiler(problem);
+    let binary = compilation.status.is_ok();
+    report.compilation = Some(compilation);
 
-    println!();
-    let binary = execute_compiler(&problem, generated_sources.as_path());
-    let (passed_tests, total_tests) = run_tests(&tests, problem.output.as_path(), !binary);
+    let sanitized = if config.sanitize && binary {
+        execute_sanitized_compiler(problem)
+    } else {
+        None
+    };
+
+    let (summary, suites) = run_tests(
+        &tests,
+        &problem.backend.program(problem),
+        sanitized.as_ref(),
+        !binary,
+        &config.limits,
+        config.jobs,
+    );
+    report.tests = suites;
+
+    let code = show_veredict(binary, &summary);
+    report.veredict = Some(report::Veredict {
+        status: get_veredict(binary, &summary),
+        summary,
+    });
 
-    Ok(show_veredict(binary, passed_tests, total_tests))
+    Ok(code)
+}
+
+fn watch_problem(config: &config::Config) -> Result<exitcode::ExitCode, Error> {
+    let problem = load_problem(config)?;
+    let resources = fetch::fetch_resources(&problem, config)?;
+    let mut snapshot = watch::Snapshot::take(&problem);
+
+    loop {
+        if ux::is_human_output() {
+            print!("{}{}", clear::All, cursor::Goto(1, 1));
+        }
+
+        // Reload the problem, as the changes may have added or removed the main() function
+        let result = load_problem(config).and_then(|problem| {
+            let mut report = report::Report::new(&problem.id);
+            report.fetch = resources.tasks.clone();
+            build_and_test(&problem, resources.tests, config, &mut report)?;
+            Ok(report)
+        });
+        match result {
+            Ok(report) => {
+                if let Some(junit) = &config.junit {
+                    if let Err(e) = write_junit(std::slice::from_ref(&report), junit) {
+                        error!("{}", e);
+                    }
+                }
+                if !ux::is_human_output() {
+                    report::print_json(&report);
+                }
+            }
+            Err(e) => error!("{}", e),
+        }
+
+        info!("Watching for changes in your code and tests (press Ctrl+C to exit)...");
+        watch::wait_for_changes(&problem, &mut snapshot);
+    }
+}
+
+fn batch_problems(
+    config: &config::Config,
+    root: Option<&path::Path>,
+) -> Result<exitcode::ExitCode, Error> {
+    let root = root.unwrap_or(config.problem_dir.as_path());
+    let problem_dirs = problem::find_problem_dirs(root).map_err(|e| Error {
+        description: format!(
+            "Couldn't look for problems in {}: {}",
+            root.to_string_lossy(),
+            e
+        ),
+        exitcode: exitcode::IOERR,
+    })?;
+    info!(
+        "Found {} problems in {}",
+        problem_dirs.len(),
+        root.to_string_lossy()
+    );
+
+    let mut code = exitcode::OK;
+    let mut reports = Vec::new();
+    for problem_dir in problem_dirs {
+        let id = problem_dir
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .to_string();
+        if ux::is_human_output() {
+            println!(
+                "\n{}{}==> {}{}",
+                style::Bold,
+                color::Fg(color::Blue),
+                id,
+                style::Reset
+            );
+        }
+
+        let config = config::Config {
+            problem_dir,
+            ..config.clone()
+        };
+        let report = match check_problem(&config) {
+            Ok((problem_code, report)) => {
+                if code == exitcode::OK {
+                    code = problem_code;
+                }
+                report
+            }
+            Err(e) => {
+                error!("{}", e);
+                if code == exitcode::OK {
+                    code = *e;
+                }
+                report::Report {
+                    error: Some(e.to_string()),
+                    ..report::Report::new(&id)
+                }
+            }
+        };
+        reports.push(report);
+    }
+
+    if let Some(junit) = &config.junit {
+        write_junit(&reports, junit)?;
+    }
+
+    if ux::is_human_output() {
+        show_batch_summary(&reports);
+    } else {
+        report::print_json(&reports);
+    }
+    Ok(code)
+}
+
+fn write_junit(reports: &[report::Report], path: &path::Path) -> Result<(), Error> {
+    debug!("Writing the JUnit report to {}", path.to_string_lossy());
+    report::write_junit(reports, path).map_err(|e| Error {
+        description: format!("Couldn't write the JUnit report: {}", e),
+        exitcode: exitcode::CANTCREAT,
+    })
+}
+
+fn fetch_problem(config: &config::Config) -> Result<exitcode::ExitCode, Error> {
+    let problem = load_problem(config)?;
+    let mut report = report::Report::new(&problem.id);
+    report.fetch = fetch::fetch_resources(&problem, config)?.tasks;
+
+    if !ux::is_human_output() {
+        report::print_json(&report);
+    }
+    Ok(exitcode::OK)
+}
+
+fn submit_problem(config: &config::Config) -> Result<exitcode::ExitCode, Error> {
+    let problem = load_problem(config)?;
+    let mut report = report::Report::new(&problem.id);
+
+    let submission = fetch::submit_solution(&problem, config)?;
+    let code = show_submission(&submission);
+    report.submission = Some(submission);
+
+    if !ux::is_human_output() {
+        report::print_json(&report);
+    }
+    Ok(code)
+}
+
+fn offer_submission(config: &config::Config) -> Result<Option<report::Submission>, Error> {
+    if config.offline {
+        debug!("Working offline, not offering to submit the solution");
+        return Ok(None);
+    }
+    if !ux::confirm("\nAll the tests passed. Do you want to submit your code to jutge.org?") {
+        debug!("The solution won't be submitted");
+        return Ok(None);
+    }
+
+    let problem = load_problem(config)?;
+    fetch::submit_solution(&problem, config).map(Some)
+}
+
+fn show_submission(submission: &report::Submission) -> exitcode::ExitCode {
+    let (code, color): (_, &dyn color::Color) = match submission.verdict.as_deref() {
+        Some("AC") => (exitcode::OK, &color::Green),
+        Some(_) => (exitcode::DATAERR, &color::Red),
+        None => (exitcode::TEMPFAIL, &color::LightYellow),
+    };
+
+    if ux::is_human_output() {
+        match (&submission.verdict, &submission.description) {
+            (Some(verdict), Some(description)) => println!(
+                "{}jutge.org says: {} ({}){}",
+                color::Fg(color),
+                description,
+                verdict,
+                style::Reset
+            ),
+            _ => println!(
+                "{}jutge.org hasn't judged your submission yet, check it later at {}{}",
+                color::Fg(color),
+                submission.url,
+                style::Reset
+            ),
+        }
+    }
+    code
+}
+
+fn show_history(config: &config::Config, get: Option<u32>) -> Result<exitcode::ExitCode, Error> {
+    let problem = load_problem(config)?;
+
+    if let Some(number) = get {
+        let path = fetch::fetch_submission_source(&problem, config, number)?;
+        info!("Submission saved to {}", path.to_string_lossy());
+        return Ok(exitcode::OK);
+    }
+
+    let submissions = fetch::fetch_history(&problem, config)?;
+    if !ux::is_human_output() {
+        report::print_json(&submissions);
+    } else if submissions.is_empty() {
+        info!("You haven't submitted any solution to {} yet", problem.id);
+    } else {
+        show_history_table(&submissions);
+    }
+    Ok(exitcode::OK)
+}
+
+fn show_history_table(submissions: &[fetch::PastSubmission]) {
+    println!(
+        "\n{}{:10}  {:19}  {:10}  VERDICT{}",
+        style::Bold,
+        "SUBMISSION",
+        "DATE",
+        "COMPILER",
+        style::Reset
+    );
+    for submission in submissions {
+        let verdict_color: &dyn color::Color = match submission.verdict.as_deref() {
+            Some("AC") => &color::Green,
+            Some(_) => &color::Red,
+            None => &color::LightYellow,
+        };
+        println!(
+            "{:10}  {:19}  {:10}  {}{}{}",
+            submission.id,
+            submission.date.as_deref().unwrap_or("-"),
+            submission.compiler.as_deref().unwrap_or("-"),
+            color::Fg(verdict_color),
+            submission.verdict.as_deref().unwrap_or("Pending"),
+            style::Reset
+        );
+    }
+}
+
+fn show_statement(config: &config::Config, text: bool) -> Result<exitcode::ExitCode, Error> {
+    // The statement is usually read before writing any code
+    let problem = Problem::without_source(config)?;
+    let mut report = report::Report::new(&problem.id);
+    let statement = fetch::fetch_statement(&problem, config)?;
+    report.fetch = statement.tasks;
+
+    if !ux::is_human_output() {
+        report::print_json(&report);
+        return Ok(exitcode::OK);
+    }
+
+    for file in ["statement.pdf", "statement.html"] {
+        let path = problem.work_dir.join(file);
+        if path.is_file() {
+            info!("Statement saved to {}", path.to_string_lossy());
+        }
+    }
+
+    if text && !statement.html {
+        return Err(Error {
+            description: String::from(
+                "Couldn't download the HTML statement, which is needed to show it as text!",
+            ),
+            exitcode: exitcode::UNAVAILABLE,
+        });
+    } else if text {
+        let html =
+            fs::read_to_string(problem.work_dir.join("statement.html")).map_err(|e| Error {
+                description: format!("Couldn't read the HTML statement: {}", e),
+                exitcode: exitcode::IOERR,
+            })?;
+        println!("\n{}", statement::to_text(&html));
+    }
+    Ok(exitcode::OK)
+}
+
+fn clean_problem(config: &config::Config) -> Result<exitcode::ExitCode, Error> {
+    let problem = load_problem(config)?;
+
+    let to_io_error = |e: io::Error| Error {
+        description: format!("Couldn't clean the problem files: {}", e),
+        exitcode: exitcode::IOERR,
+    };
+
+    if problem.output.is_file() {
+        debug!("Removing {}", problem.output.to_string_lossy());
+        fs::remove_file(problem.output.as_path()).map_err(to_io_error)?;
+    }
+    if problem.tmp_dir.is_dir() {
+        debug!("Removing {}", problem.tmp_dir.to_string_lossy());
+        fs::remove_dir_all(problem.tmp_dir.as_path()).map_err(to_io_error)?;
+    }
+
+    info!(
+        "Removed the compiled binary and the temporal files of {}",
+        problem.id
+    );
+    Ok(exitcode::OK)
+}
+
+fn manage_cache(
+    config: &config::Config,
+    action: &cli::CacheAction,
+) -> Result<exitcode::ExitCode, Error> {
+    let to_io_error = |e: io::Error| Error {
+        description: format!("Couldn't manage the cache: {}", e),
+        exitcode: exitcode::IOERR,
+    };
+    let find_entry = |id: &str| match cache::find(&config.cache_dir, id).map_err(to_io_error)? {
+        Some(entry) => Ok(entry),
+        None => Err(Error {
+            description: format!("There aren't any downloaded files for {}", id),
+            exitcode: exitcode::DATAERR,
+        }),
+    };
+
+    match action {
+        cli::CacheAction::List { id } => {
+            let entries = match id {
+                Some(id) => vec![find_entry(id)?],
+                None => cache::list(&config.cache_dir).map_err(to_io_error)?,
+            };
+            if ux::is_human_output() {
+                show_cache_entries(&entries);
+            } else {
+                report::print_json(&entries);
+            }
+        }
+        cli::CacheAction::Clean { id } => {
+            let entries = match id {
+                Some(id) => vec![find_entry(id)?],
+                None => cache::list(&config.cache_dir).map_err(to_io_error)?,
+            };
+            for entry in &entries {
+                debug!("Removing {}", entry.path.to_string_lossy());
+                entry.remove().map_err(to_io_error)?;
+            }
+            info!("Removed the downloaded files of {} problems", entries.len());
+        }
+        cli::CacheAction::Refresh { id } => {
+            let current_id = config
+                .problem_dir
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string());
+            let id = match id.as_ref().or(current_id.as_ref()) {
+                Some(id) => id,
+                None => return Err(problem::Error::BadPathFormat.into()),
+            };
+
+            let problem = Problem::from_id(config, id)?;
+            if config.offline {
+                // The files would be removed without any way to download them again
+                return Err(Error {
+                    description: String::from("Can't refresh the files while working offline!"),
+                    exitcode: exitcode::UNAVAILABLE,
+                });
+            }
+            if let Some(entry) = cache::find(&config.cache_dir, id).map_err(to_io_error)? {
+                debug!("Removing {}", entry.path.to_string_lossy());
+                entry.remove().map_err(to_io_error)?;
+            }
+            fs::create_dir_all(&problem.work_dir).map_err(to_io_error)?;
+
+            let mut report = report::Report::new(&problem.id);
+            report.fetch = fetch::fetch_resources(&problem, config)?.tasks;
+            if !ux::is_human_output() {
+                report::print_json(&report);
+            }
+        }
+    }
+
+    Ok(exitcode::OK)
+}
+
+fn show_cache_entries(entries: &[cache::Entry]) {
+    let mark = |present: bool| if present { "yes" } else { "no" };
+
+    println!(
+        "{}{:12}  {:>10}  {:3}  {:5}  MAIN(){}",
+        style::Bold,
+        "PROBLEM",
+        "SIZE",
+        "ZIP",
+        "TESTS",
+        style::Reset
+    );
+    for entry in entries {
+        println!(
+            "{:12}  {:>10}  {:3}  {:5}  {}",
+            entry.id,
+            cache::human_size(entry.size),
+            mark(entry.zip),
+            mark(entry.samples),
+            mark(entry.main)
+        );
+    }
+    info!(
+        "{} problems, {} in total",
+        entries.len(),
+        cache::human_size(entries.iter().map(|e| e.size).sum())
+    );
+}
+
+fn show_config(config: &config::Config) -> Result<exitcode::ExitCode, Error> {
+    info!(
+        "Config file: {}",
+        config.config_file_path().to_string_lossy()
+    );
+    info!("Cache dir: {}", config.cache_dir.to_string_lossy());
+    info!("Temporal dir: {}", config.tmp_dir.to_string_lossy());
+    let settings = config.problem_settings()?;
+    info!(
+        "Source file: {}",
+        settings
+            .source
+            .map_or(String::from("main.* (autodetected)"), |s| s
+                .to_string_lossy()
+                .to_string())
+    );
+    info!(
+        "Compiler: {}",
+        settings
+            .compiler
+            .as_deref()
+            .unwrap_or("chosen from the source file extension")
+    );
+    info!(
+        "Available compilers: {}",
+        compilation::BACKENDS
+            .iter()
+            .map(|c| c.name())
+            .chain(config.compilers.iter().map(compilation::Backend::name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    info!(
+        "jutge.org credentials: {}",
+        if config.credentials.is_some() {
+            "configured"
+        } else {
+            "not configured"
+        }
+    );
+
+    Ok(exitcode::OK)
 }
 
 fn load_tests(
@@ -84,73 +616,180 @@ fn load_tests(
     }
 }
 
-fn execute_compiler(problem: &Problem, generated_sources: &path::Path) -> bool {
+fn execute_compiler(problem: &Problem) -> report::Compilation {
     const TASK: &str = "Compilation";
 
     ux::show_task_status(TASK, ux::TaskType::Test, &ux::TaskStatus::InProgress);
-    match compilation::P1XX.compile_problem(problem, generated_sources) {
-        Ok(()) => {
+    let result = compilation::compile_cached(problem.backend, problem);
+    match &result {
+        Ok(_) => {
             ux::show_task_status(TASK, ux::TaskType::Test, &ux::TaskStatus::Pass);
-            true
         }
         Err(e) => {
             ux::show_task_status(TASK, ux::TaskType::Test, &ux::TaskStatus::Fail);
-            match e.error {
+            match &e.error {
                 compilation::Error::CompilerError(stderr) => {
+                    // Fall back to the raw output when it can't be parsed (e.g. linker errors)
+                    let output = if e.diagnostics.is_empty() {
+                        stderr.clone()
+                    } else {
+                        compilation::render_diagnostics(&e.diagnostics)
+                    };
                     ux::show_task_output(
                         format!("Compilation output (pass {})", e.pass).as_str(),
-                        &stderr,
+                        &output,
                     );
                 }
                 _ => error!("Compilation failed unexpectedly: {}", e),
             }
-            false
+        }
+    }
+
+    report::Compilation::new(problem.backend, &result)
+}
+
+fn execute_sanitized_compiler(problem: &Problem) -> Option<testing::Program> {
+    const TASK: &str = "Compilation with sanitizers";
+
+    ux::show_task_status(TASK, ux::TaskType::Test, &ux::TaskStatus::InProgress);
+    match problem.backend.build_sanitized(problem) {
+        Some(Ok(program)) => {
+            ux::show_task_status(TASK, ux::TaskType::Test, &ux::TaskStatus::Pass);
+            Some(program)
+        }
+        Some(Err(e)) => {
+            ux::show_task_status(TASK, ux::TaskType::Test, &ux::TaskStatus::SkipBad);
+            warning!("Couldn't compile with sanitizers: {}", e);
+            None
+        }
+        None => {
+            ux::show_task_status(TASK, ux::TaskType::Test, &ux::TaskStatus::SkipBad);
+            warning!(
+                "The {} compiler doesn't support sanitizers",
+                problem.backend.name()
+            );
+            None
         }
     }
 }
 
 fn run_tests(
     testsuites: &[Option<testing::TestSuite>],
-    binary: &path::Path,
+    program: &testing::Program,
+    sanitized: Option<&testing::Program>,
     skip_tests: bool,
-) -> (usize, usize) {
-    let mut passed: usize = 0;
-    let mut total: usize = 0;
+    limits: &testing::Limits,
+    jobs: usize,
+) -> (testing::Summary, Vec<report::TestSuite>) {
+    let mut summary = testing::Summary::default();
+    let mut reports = Vec::new();
 
     for testsuite in testsuites.iter().flatten() {
-        passed += testsuite.run(binary, skip_tests);
-        total += testsuite.count();
+        let (suite_summary, results) = testsuite.run(program, sanitized, skip_tests, limits, jobs);
+        summary += suite_summary;
+        reports.push(report::TestSuite::new(testsuite, &results));
     }
 
-    (passed, total)
+    (summary, reports)
 }
 
-fn show_veredict(compiles: bool, passed: usize, total: usize) -> i32 {
-    let code = if !compiles {
-        print!("{}Your code doesn't compile!", color::Fg(color::Red));
-        exitcode::DATAERR
-    } else if total == 0 {
-        print!("{}Your code compiles but you should test it before sumbitting. Try to add some tests to the folder.", color::Fg(color::LightYellow));
-        exitcode::OK
-    } else if passed != total {
-        print!(
-            "{}DON'T submit your code to jutge.org!",
-            color::Fg(color::Red)
+fn show_batch_summary(reports: &[report::Report]) {
+    let width = reports
+        .iter()
+        .map(|r| r.problem.len())
+        .max()
+        .unwrap_or(0)
+        .max("PROBLEM".len());
+
+    println!();
+    println!(
+        "{}{:w$}  {:7}  VEREDICT{}",
+        style::Bold,
+        "PROBLEM",
+        "TESTS",
+        style::Reset,
+        w = width
+    );
+    for report in reports {
+        let tests = match &report.veredict {
+            Some(veredict) => format!("{}/{}", veredict.summary.passed, veredict.summary.total),
+            None => String::from("-"),
+        };
+        let veredict: (&dyn color::Color, &str) = match report.veredict.as_ref().map(|v| v.status) {
+            Some(report::VeredictStatus::ReadyToSubmit) => (&color::Green, "Ready to submit"),
+            Some(report::VeredictStatus::Untested) => {
+                (&color::LightYellow, "Compiles, but untested")
+            }
+            Some(report::VeredictStatus::DontSubmit) => (&color::Red, "DON'T submit"),
+            Some(report::VeredictStatus::DoesntCompile) => (&color::Red, "Doesn't compile"),
+            None => (&color::Red, "Error"),
+        };
+        println!(
+            "{:w$}  {:7}  {}{}{}",
+            report.problem,
+            tests,
+            color::Fg(veredict.0),
+            veredict.1,
+            style::Reset,
+            w = width
         );
-        exitcode::DATAERR
+    }
+}
+
+fn get_veredict(compiles: bool, summary: &testing::Summary) -> report::VeredictStatus {
+    if !compiles {
+        report::VeredictStatus::DoesntCompile
+    } else if summary.total == 0 {
+        report::VeredictStatus::Untested
+    } else if summary.passed != summary.total {
+        report::VeredictStatus::DontSubmit
     } else {
-        print!(
+        report::VeredictStatus::ReadyToSubmit
+    }
+}
+
+fn show_veredict(compiles: bool, summary: &testing::Summary) -> i32 {
+    let (passed, total) = (summary.passed, summary.total);
+    let veredict = get_veredict(compiles, summary);
+    let code = match veredict {
+        report::VeredictStatus::DoesntCompile | report::VeredictStatus::DontSubmit => {
+            exitcode::DATAERR
+        }
+        report::VeredictStatus::Untested | report::VeredictStatus::ReadyToSubmit => exitcode::OK,
+    };
+
+    if !ux::is_human_output() {
+        return code;
+    }
+
+    match veredict {
+        report::VeredictStatus::DoesntCompile => {
+            print!("{}Your code doesn't compile!", color::Fg(color::Red))
+        }
+        report::VeredictStatus::Untested => print!("{}Your code compiles but you should test it before sumbitting. Try to add some tests to the folder.", color::Fg(color::LightYellow)),
+        report::VeredictStatus::DontSubmit => print!(
+            "{}DON'T submit your code to jutge.org!",
+            color::Fg(color::Red)
+        ),
+        report::VeredictStatus::ReadyToSubmit => print!(
             "{}You're ready to submit your code to jutge.org!",
             color::Fg(color::Green)
+        ),
+    }
+    print!(" ({} out of {} tests passed", passed, total);
+    if summary.time_limit_exceeded > 0 {
+        print!(", {} exceeded the time limit", summary.time_limit_exceeded);
+    }
+    if summary.memory_limit_exceeded > 0 {
+        print!(
+            ", {} exceeded the memory limit",
+            summary.memory_limit_exceeded
         );
-        exitcode::OK
-    };
-    println!(
-        " ({} out of {} tests passed){}",
-        passed,
-        total,
-        style::Reset
-    );
+    }
+    if summary.execution_errors > 0 {
+        print!(", {} had execution errors", summary.execution_errors);
+    }
+    println!("){}", style::Reset);
 
     code
 }
@@ -159,11 +798,41 @@ fn show_veredict(compiles: bool, passed: usize, total: usize) -> i32 {
 mod test {
     use super::*;
 
+    fn summary(passed: usize, total: usize, time_limit_exceeded: usize) -> testing::Summary {
+        testing::Summary {
+            passed,
+            total,
+            time_limit_exceeded,
+            ..Default::default()
+        }
+    }
+
     #[test]
     fn show_veredict_test() {
-        assert_eq!(show_veredict(false, 0, 0), exitcode::DATAERR);
-        assert_eq!(show_veredict(true, 0, 0), exitcode::OK);
-        assert_eq!(show_veredict(true, 0, 1), exitcode::DATAERR);
-        assert_eq!(show_veredict(true, 1, 1), exitcode::OK);
+        assert_eq!(show_veredict(false, &summary(0, 0, 0)), exitcode::DATAERR);
+        assert_eq!(show_veredict(true, &summary(0, 0, 0)), exitcode::OK);
+        assert_eq!(show_veredict(true, &summary(0, 1, 0)), exitcode::DATAERR);
+        assert_eq!(show_veredict(true, &summary(1, 1, 0)), exitcode::OK);
+        assert_eq!(show_veredict(true, &summary(1, 2, 1)), exitcode::DATAERR);
+    }
+
+    #[test]
+    fn get_veredict_test() {
+        assert_eq!(
+            get_veredict(false, &summary(0, 0, 0)),
+            report::VeredictStatus::DoesntCompile
+        );
+        assert_eq!(
+            get_veredict(true, &summary(0, 0, 0)),
+            report::VeredictStatus::Untested
+        );
+        assert_eq!(
+            get_veredict(true, &summary(0, 1, 1)),
+            report::VeredictStatus::DontSubmit
+        );
+        assert_eq!(
+            get_veredict(true, &summary(1, 1, 0)),
+            report::VeredictStatus::ReadyToSubmit
+        );
     }
 }
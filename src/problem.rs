@@ -3,19 +3,24 @@ use std::fs;
 use std::io;
 use std::path;
 
-use crate::{config, debug};
+use crate::{compilation, config, debug};
 use regex::Regex;
 
 #[derive(Debug)]
-pub struct Problem {
+pub struct Problem<'a> {
     pub id: String,
+    pub backend: &'a dyn compilation::Backend,
     pub source: path::PathBuf,
     pub output: path::PathBuf,
     pub work_dir: path::PathBuf,
     pub tmp_dir: path::PathBuf,
-    pub has_main: bool,
+    /// None if the source wasn't read, as when downloading the files of another problem
+    pub has_main: Option<bool>,
     pub zip_url: String,
-    pub main_cc_url: String,
+    pub main_url: String,
+    pub pdf_url: String,
+    pub html_url: String,
+    pub submit_url: String,
 }
 
 #[derive(Debug)]
@@ -24,7 +29,10 @@ pub enum Error {
     NonDirectoryPath,
     BadPathFormat,
     BadId(IdError),
-    BadSource(SourceError),
+    BadSource(path::PathBuf, SourceError),
+    BadConfig(config::Error),
+    UnknownCompiler(String),
+    UnsupportedSource(path::PathBuf),
     CantCreateWorkDir(io::Error),
 }
 
@@ -35,7 +43,14 @@ impl fmt::Display for Error {
             Error::NonDirectoryPath => write!(f, "The path isn't a directory!"),
             Error::BadPathFormat => write!(f, "The path ends in \"..\"!"),
             Error::BadId(e) => write!(f, "Problem id is wrong: {}", e),
-            Error::BadSource(e) => write!(f, "Problem with main.cc: {}", e),
+            Error::BadSource(path, e) => {
+                write!(f, "Problem with {}: {}", path.to_string_lossy(), e)
+            }
+            Error::BadConfig(e) => write!(f, "Wrong problem configuration: {}", e),
+            Error::UnknownCompiler(name) => write!(f, "Unknown compiler: {}", name),
+            Error::UnsupportedSource(path) => {
+                write!(f, "There isn't any compiler for {}", path.to_string_lossy())
+            }
             Error::CantCreateWorkDir(e) => {
                 write!(f, "Can't create a working dir for the program: {}", e)
             }
@@ -50,7 +65,11 @@ impl From<Error> for crate::Error {
             | Error::NonDirectoryPath
             | Error::BadPathFormat
             | Error::CantCreateWorkDir(_) => exitcode::OSERR,
-            Error::BadId(_) | Error::BadSource(_) => exitcode::DATAERR,
+            Error::BadId(_)
+            | Error::BadSource(..)
+            | Error::BadConfig(_)
+            | Error::UnknownCompiler(_)
+            | Error::UnsupportedSource(_) => exitcode::DATAERR,
         };
 
         crate::Error {
@@ -92,8 +111,23 @@ impl fmt::Display for SourceError {
     }
 }
 
-impl Problem {
-    pub fn new(config: &config::Config) -> Result<Self, Error> {
+impl<'a> Problem<'a> {
+    pub fn new(config: &'a config::Config) -> Result<Self, Error> {
+        let mut problem = Problem::without_source(config)?;
+
+        let has_main = if problem.backend.allows_nomain() {
+            file_has_main(&problem.source)
+        } else {
+            read_source(&problem.source).map(|_| true)
+        }
+        .map_err(|e| Error::BadSource(problem.source.clone(), e))?;
+        problem.has_main = Some(has_main);
+
+        Ok(problem)
+    }
+
+    /// Problem of the problem folder, which may not have a source file yet
+    pub fn without_source(config: &'a config::Config) -> Result<Self, Error> {
         if !config.problem_dir.exists() {
             return Err(Error::NonExistingPath);
         } else if !config.problem_dir.is_dir() {
@@ -106,36 +140,105 @@ impl Problem {
             .ok_or(Error::BadPathFormat)?
             .to_string_lossy()
             .into();
-        let id = verify_id(id).map_err(Error::BadId)?;
+        Problem::from_id(config, &id)
+    }
+
+    /// Problem with the given id, using the settings of the problem folder but without reading
+    /// the source file, so it's only good for downloading the problem files
+    pub fn from_id(config: &'a config::Config, id: &str) -> Result<Self, Error> {
+        let id = verify_id(id.to_owned()).map_err(Error::BadId)?;
 
-        let source = config.problem_dir.join("main.cc");
-        let output = config.problem_dir.join("main.x");
+        let settings = config.problem_settings().map_err(Error::BadConfig)?;
+        let source = match &settings.source {
+            Some(source) => config.problem_dir.join(source),
+            None => find_default_source(&config.problem_dir),
+        };
+        let backend = match &settings.compiler {
+            Some(name) => config
+                .find_compiler(name)
+                .ok_or_else(|| Error::UnknownCompiler(name.clone()))?,
+            None => compilation::backend_for_source(&source)
+                .ok_or_else(|| Error::UnsupportedSource(source.clone()))?,
+        };
         let work_dir = config.cache_dir.join(&id);
         let tmp_dir = config.tmp_dir.join(&id);
+        let output = match &settings.output {
+            Some(output) if backend.produces_binary() => config.problem_dir.join(output),
+            _ => backend.default_output(&source, &tmp_dir),
+        };
 
         fs::create_dir_all(work_dir.as_path()).map_err(Error::CantCreateWorkDir)?;
-
-        let has_main = file_has_main(&source).map_err(Error::BadSource)?;
+        fs::create_dir_all(tmp_dir.as_path()).map_err(Error::CantCreateWorkDir)?;
 
         let problem_url = format!("https://jutge.org/problems/{}", id);
         let zip_url = format!("{}/zip", problem_url);
-        let main_cc_url = format!("{}/main/cc", problem_url);
+        let main_url = format!("{}/main/{}", problem_url, backend.extensions()[0]);
+        let pdf_url = format!("{}/pdf", problem_url);
+        let submit_url = format!("{}/submissions", problem_url);
 
         Ok(Problem {
             id,
+            backend,
             source,
             output,
             work_dir,
             tmp_dir,
-            has_main,
+            has_main: None,
             zip_url,
-            main_cc_url,
+            main_url,
+            pdf_url,
+            html_url: problem_url,
+            submit_url,
         })
     }
+
+    /// Path where the main() provided by jutge.org is downloaded
+    pub fn downloaded_main(&self) -> path::PathBuf {
+        self.work_dir
+            .join("main")
+            .with_extension(self.backend.extensions()[0])
+    }
+}
+
+fn find_default_source(problem_dir: &path::Path) -> path::PathBuf {
+    let candidates: Vec<path::PathBuf> = compilation::BACKENDS
+        .iter()
+        .map(|b| problem_dir.join("main").with_extension(b.extensions()[0]))
+        .collect();
+    candidates
+        .iter()
+        .find(|s| s.is_file())
+        .unwrap_or(&candidates[0])
+        .to_owned()
+}
+
+pub fn find_problem_dirs(root: &path::Path) -> io::Result<Vec<path::PathBuf>> {
+    let mut problem_dirs = Vec::new();
+    for entry in fs::read_dir(root)? {
+        let entry = entry?;
+        // Symlinks aren't followed to avoid walking the same folders forever
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') {
+            continue;
+        } else if verify_id(name).is_ok() {
+            debug!("Found problem folder {}", path.to_string_lossy());
+            problem_dirs.push(path);
+        } else {
+            problem_dirs.append(&mut find_problem_dirs(&path)?);
+        }
+    }
+
+    problem_dirs.sort();
+    Ok(problem_dirs)
 }
 
 fn verify_id(id: String) -> Result<String, IdError> {
-    let re = Regex::new(r"[A-Z]\d{5}_[a-z]{2}").unwrap();
+    let re = Regex::new(r"^[A-Z]\d{5}_[a-z]{2}$").unwrap();
     if !re.is_match(&id) {
         return Err(IdError::InvalidId);
     }
@@ -147,7 +250,7 @@ fn verify_id(id: String) -> Result<String, IdError> {
     }
 }
 
-fn file_has_main(path: &path::Path) -> Result<bool, SourceError> {
+fn read_source(path: &path::Path) -> Result<String, SourceError> {
     debug!("Attempting to read {}", path.to_string_lossy());
     if !path.exists() {
         Err(SourceError::NonExistingPath)
@@ -156,11 +259,16 @@ fn file_has_main(path: &path::Path) -> Result<bool, SourceError> {
     } else {
         let contents = fs::read_to_string(path).map_err(SourceError::CantRead)?;
         debug!("Done reading {}", path.to_string_lossy());
-        let re = Regex::new(r"int\s+main\s*(\s*)").unwrap();
-        Ok(re.is_match(&contents))
+        Ok(contents)
     }
 }
 
+fn file_has_main(path: &path::Path) -> Result<bool, SourceError> {
+    let contents = read_source(path)?;
+    let re = Regex::new(r"int\s+main\s*(\s*)").unwrap();
+    Ok(re.is_match(&contents))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -179,12 +287,46 @@ mod test {
             test_utils::get_tests_folder().join("problems/P00000_xx/main.x")
         );
         assert!(!p.work_dir.to_string_lossy().is_empty());
-        assert!(p.has_main);
+        assert_eq!(p.has_main, Some(true));
         assert_eq!(p.zip_url, "https://jutge.org/problems/P00000_xx/zip");
-        assert_eq!(
-            p.main_cc_url,
-            "https://jutge.org/problems/P00000_xx/main/cc"
-        ); // Irrelevant, but still tested
+        assert_eq!(p.main_url, "https://jutge.org/problems/P00000_xx/main/cc"); // Irrelevant, but still tested
+    }
+
+    #[test]
+    fn generate_problem_without_source() {
+        let mut config = test_utils::get_config();
+        config.problem_dir = test_utils::get_tests_folder()
+            .join("problems")
+            .join("P00003_xx");
+        assert!(!config.problem_dir.exists());
+        assert!(matches!(
+            Problem::without_source(&config),
+            Err(Error::NonExistingPath)
+        ));
+
+        config.problem_dir = test_utils::get_tests_folder()
+            .join("problems")
+            .join("P99999_xx");
+        config.problem_args.source = Some(path::PathBuf::from("missing.cc"));
+        assert!(matches!(Problem::new(&config), Err(Error::BadSource(..))));
+        let p = Problem::without_source(&config).unwrap();
+        assert_eq!(p.id, "P99999_xx");
+        assert_eq!(p.html_url, "https://jutge.org/problems/P99999_xx");
+    }
+
+    #[test]
+    fn generate_problem_from_id() {
+        let config = test_utils::get_config();
+        let p = Problem::from_id(&config, "P00000_xx").unwrap();
+        assert_eq!(p.id, "P00000_xx");
+        assert_eq!(p.work_dir, config.cache_dir.join("P00000_xx"));
+        assert_eq!(p.has_main, None);
+        assert_eq!(p.zip_url, "https://jutge.org/problems/P00000_xx/zip");
+
+        match Problem::from_id(&config, "../P00000_xx") {
+            Err(Error::BadId(IdError::InvalidId)) => {}
+            _ => panic!(),
+        }
     }
 
     #[test]
@@ -200,12 +342,9 @@ mod test {
             test_utils::get_tests_folder().join("problems/P00001_xx/main.x")
         );
         assert!(!p.work_dir.to_string_lossy().is_empty());
-        assert!(!p.has_main);
+        assert_eq!(p.has_main, Some(false));
         assert_eq!(p.zip_url, "https://jutge.org/problems/P00001_xx/zip");
-        assert_eq!(
-            p.main_cc_url,
-            "https://jutge.org/problems/P00001_xx/main/cc"
-        );
+        assert_eq!(p.main_url, "https://jutge.org/problems/P00001_xx/main/cc");
     }
 
     #[test]
@@ -221,17 +360,69 @@ mod test {
             test_utils::get_tests_folder().join("problems/X00000_xx/main.x")
         );
         assert!(!p.work_dir.to_string_lossy().is_empty());
-        assert!(!p.has_main);
+        assert_eq!(p.has_main, Some(false));
         assert_eq!(p.zip_url, "https://jutge.org/problems/X00000_xx/zip");
+        assert_eq!(p.main_url, "https://jutge.org/problems/X00000_xx/main/cc");
+    }
+
+    #[test]
+    fn generate_python_problem() {
+        let p = test_utils::get_problem("P00002_xx");
+        assert_eq!(p.backend.name(), "python3");
         assert_eq!(
-            p.main_cc_url,
-            "https://jutge.org/problems/X00000_xx/main/cc"
+            p.source,
+            test_utils::get_tests_folder().join("problems/P00002_xx/main.py")
         );
+        assert_eq!(p.output, p.tmp_dir.join("main.pyc"));
+        assert_eq!(p.has_main, Some(true));
+    }
+
+    #[test]
+    fn generate_problem_custom_source() {
+        let mut config = test_utils::get_config();
+        config.problem_dir = test_utils::get_tests_folder().join("problems/P00000_xx");
+        config.problem_args.source = Some(path::PathBuf::from("main.cc"));
+        config.problem_args.output = Some(path::PathBuf::from("solution"));
+        let p = Problem::new(&config).unwrap();
+        assert_eq!(p.source, config.problem_dir.join("main.cc"));
+        assert_eq!(p.output, config.problem_dir.join("solution"));
+
+        config.problem_args.source = Some(path::PathBuf::from("fast.cc"));
+        config.problem_args.output = None;
+        match Problem::new(&config) {
+            Err(Error::BadSource(path, SourceError::NonExistingPath)) => {
+                assert_eq!(path, config.problem_dir.join("fast.cc"))
+            }
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn generate_problem_local_config() {
+        let dir = test_utils::SelfCleaningTmp::new("problem", "P00003_xx");
+        fs::write(dir.join("fast.cc"), "int main() {}\n").unwrap();
+        fs::write(
+            dir.join("advocat.ini"),
+            "[problem]\nsource = fast.cc\ncompiler = clang++\n",
+        )
+        .unwrap();
+
+        let mut config = test_utils::get_config();
+        config.problem_dir = dir.to_path_buf();
+        let p = Problem::new(&config).unwrap();
+        assert_eq!(p.source, dir.join("fast.cc"));
+        assert_eq!(p.output, dir.join("fast.x"));
+        assert_eq!(p.backend.name(), "clang++");
+
+        // The command line has the last word
+        config.problem_args.compiler = Some(String::from("g++"));
+        let p = Problem::new(&config).unwrap();
+        assert_eq!(p.backend.name(), "g++");
     }
 
     #[test]
     fn generate_problem_non_existing() {
-        match test_utils::try_get_problem("foobar") {
+        match Problem::new(&test_utils::get_problem_config("foobar")) {
             Err(Error::NonExistingPath) => {}
             _ => panic!(),
         }
@@ -239,14 +430,14 @@ mod test {
 
     #[test]
     fn generate_problem_non_directory() {
-        match test_utils::try_get_problem("P00000_xx/main.cc") {
+        match Problem::new(&test_utils::get_problem_config("P00000_xx/main.cc")) {
             Err(Error::NonDirectoryPath) => {}
             _ => panic!(),
         }
     }
     #[test]
     fn generate_problem_bad_format() {
-        match test_utils::try_get_problem("..") {
+        match Problem::new(&test_utils::get_problem_config("..")) {
             Err(Error::BadPathFormat) => {}
             _ => panic!(),
         }
@@ -254,7 +445,7 @@ mod test {
 
     #[test]
     fn generate_problem_bad_id() {
-        match test_utils::try_get_problem("") {
+        match Problem::new(&test_utils::get_problem_config("")) {
             Err(Error::BadId(_)) => {}
             _ => panic!(),
         }
@@ -262,8 +453,8 @@ mod test {
 
     #[test]
     fn generate_problem_bad_main() {
-        match test_utils::try_get_problem("P99999_xx") {
-            Err(Error::BadSource(_)) => {}
+        match Problem::new(&test_utils::get_problem_config("P99999_xx")) {
+            Err(Error::BadSource(..)) => {}
             _ => panic!(),
         }
     }
@@ -290,6 +481,42 @@ mod test {
     fn verify_invalid_id() {
         let id = String::from("FooBar");
         assert_eq!(verify_id(id), Err(IdError::InvalidId));
+        let id = String::from("P00000_xx.bak");
+        assert_eq!(verify_id(id), Err(IdError::InvalidId));
+    }
+
+    #[test]
+    fn find_problem_dirs_test() {
+        let tests_folder = test_utils::get_tests_folder();
+        let problem_dirs = find_problem_dirs(&tests_folder).unwrap();
+        assert_eq!(
+            problem_dirs,
+            [
+                "P00000_xx",
+                "P00001_xx",
+                "P00002_xx",
+                "P99999_xx",
+                "X00000_xx"
+            ]
+            .iter()
+            .map(|id| tests_folder.join("problems").join(id))
+            .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn find_problem_dirs_skip_test() {
+        let tmp = test_utils::SelfCleaningTmp::new("problem", "find_problem_dirs_skip_test");
+        fs::create_dir_all(tmp.join("list").join("P00001_en")).unwrap();
+        fs::create_dir_all(tmp.join(".git").join("P00002_en")).unwrap();
+        fs::create_dir_all(tmp.join("old-P00003_en")).unwrap();
+        std::os::unix::fs::symlink(tmp.to_path_buf(), tmp.join("list").join("loop")).unwrap();
+        std::os::unix::fs::symlink(tmp.join("list"), tmp.join("P00004_en")).unwrap();
+
+        assert_eq!(
+            find_problem_dirs(&tmp).unwrap(),
+            vec![tmp.join("list").join("P00001_en")]
+        );
     }
 
     fn test_has_main(test_file: &str) -> Result<bool, SourceError> {
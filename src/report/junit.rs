@@ -0,0 +1,223 @@
+use crate::report;
+use crate::testing::DiffKind;
+use crate::ux;
+use std::fmt::Write;
+use std::{fs, io, path};
+
+pub fn write_junit(reports: &[report::Report], path: &path::Path) -> io::Result<()> {
+    fs::write(path, generate_junit(reports))
+}
+
+fn generate_junit(reports: &[report::Report]) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+
+    let name = match reports {
+        [report] => report.problem.as_str(),
+        _ => "advocat",
+    };
+    let tests = reports
+        .iter()
+        .flat_map(|report| report.tests.iter())
+        .flat_map(|suite| suite.tests.iter());
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">",
+        escape(name),
+        tests.clone().count(),
+        tests.clone().filter(|t| is_failure(t)).count(),
+        tests.clone().filter(|t| t.error.is_some()).count(),
+        tests.map(|t| t.time).sum::<f64>()
+    );
+    for report in reports {
+        for suite in &report.tests {
+            write_testsuite(&mut xml, &report.problem, suite);
+        }
+    }
+    xml.push_str("</testsuites>\n");
+
+    xml
+}
+
+fn write_testsuite(xml: &mut String, problem: &str, suite: &report::TestSuite) {
+    let _ = writeln!(
+        xml,
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{:.3}\">",
+        escape(&suite.name),
+        suite.tests.len(),
+        suite.tests.iter().filter(|t| is_failure(t)).count(),
+        suite.tests.iter().filter(|t| t.error.is_some()).count(),
+        suite.tests.iter().filter(|t| is_skipped(t)).count(),
+        suite.tests.iter().map(|t| t.time).sum::<f64>()
+    );
+
+    for test in &suite.tests {
+        let _ = write!(
+            xml,
+            "    <testcase name=\"{}\" classname=\"{}.{}\" time=\"{:.3}\"",
+            escape(&test.name),
+            escape(problem),
+            escape(&suite.name),
+            test.time
+        );
+
+        if test.status == ux::TaskStatus::Pass {
+            xml.push_str("/>\n");
+            continue;
+        }
+        xml.push_str(">\n");
+
+        if let Some(error) = &test.error {
+            let _ = writeln!(
+                xml,
+                "      <error message=\"{}\" type=\"error\"/>",
+                escape(error)
+            );
+        } else if is_skipped(test) {
+            xml.push_str("      <skipped/>\n");
+        } else {
+            let message = test
+                .execution_error
+                .clone()
+                .unwrap_or_else(|| String::from("Wrong answer"));
+            let _ = writeln!(
+                xml,
+                "      <failure message=\"{}\" type=\"{}\">{}</failure>",
+                escape(&message),
+                failure_type(test.status),
+                escape(&plain_diff(test))
+            );
+        }
+
+        if !test.stderr.is_empty() {
+            let _ = writeln!(
+                xml,
+                "      <system-err>{}</system-err>",
+                escape(&test.stderr)
+            );
+        }
+        xml.push_str("    </testcase>\n");
+    }
+
+    xml.push_str("  </testsuite>\n");
+}
+
+fn is_failure(test: &report::Test) -> bool {
+    test.error.is_none() && !test.status.is_ok() && !is_skipped(test)
+}
+
+fn is_skipped(test: &report::Test) -> bool {
+    matches!(
+        test.status,
+        ux::TaskStatus::SkipBad | ux::TaskStatus::SkipGood
+    )
+}
+
+fn failure_type(status: ux::TaskStatus) -> &'static str {
+    match status {
+        ux::TaskStatus::TimeLimitExceeded => "time_limit_exceeded",
+        ux::TaskStatus::MemoryLimitExceeded => "memory_limit_exceeded",
+        ux::TaskStatus::ExecutionError => "execution_error",
+        _ => "wrong_answer",
+    }
+}
+
+fn plain_diff(test: &report::Test) -> String {
+    let mut diff = String::new();
+    for hunk in &test.diff {
+        let prefix = match hunk.kind {
+            DiffKind::Both => ' ',
+            DiffKind::Expected => '-',
+            DiffKind::Output => '+',
+        };
+        for line in &hunk.lines {
+            diff.push(prefix);
+            diff.push_str(line);
+            diff.push('\n');
+        }
+    }
+    diff
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Other control characters aren't allowed in XML 1.0 documents
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::DiffHunk;
+
+    fn get_test(name: &str, status: ux::TaskStatus) -> report::Test {
+        report::Test {
+            name: name.to_owned(),
+            status,
+            time: 0.5,
+            exit_code: Some(0),
+            signal: None,
+            execution_error: None,
+            error: None,
+            stderr: String::new(),
+            sanitizer_report: None,
+            diff: vec![
+                DiffHunk {
+                    kind: DiffKind::Expected,
+                    lines: vec![String::from("1 < 2")],
+                },
+                DiffHunk {
+                    kind: DiffKind::Output,
+                    lines: vec![String::from("2 > 1")],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn escape_test() {
+        assert_eq!(escape("a<b>&\"c'"), "a&lt;b&gt;&amp;&quot;c&apos;");
+        assert_eq!(escape("tab\tnull\0"), "tab\tnull");
+    }
+
+    #[test]
+    fn generate_junit_test() {
+        let report = report::Report {
+            problem: String::from("P00000_xx"),
+            error: None,
+            fetch: Vec::new(),
+            compilation: None,
+            tests: vec![report::TestSuite {
+                name: String::from("user"),
+                tests: vec![
+                    get_test("user test 1", ux::TaskStatus::Pass),
+                    get_test("user test 2", ux::TaskStatus::Fail),
+                ],
+            }],
+            veredict: None,
+            submission: None,
+        };
+
+        let xml = generate_junit(&[report]);
+        assert!(xml.contains(
+            "<testsuites name=\"P00000_xx\" tests=\"2\" failures=\"1\" errors=\"0\" time=\"1.000\">"
+        ));
+        assert!(xml.contains(
+            "<testcase name=\"user test 1\" classname=\"P00000_xx.user\" time=\"0.500\"/>"
+        ));
+        assert!(xml.contains(
+            "<failure message=\"Wrong answer\" type=\"wrong_answer\">-1 &lt; 2\n+2 &gt; 1\n</failure>"
+        ));
+    }
+}
@@ -0,0 +1,196 @@
+use crate::{compilation, error, testing, ux};
+use serde::Serialize;
+use std::os::unix::process::ExitStatusExt;
+
+mod junit;
+
+pub use junit::write_junit;
+
+#[derive(Serialize)]
+pub struct Report {
+    pub problem: String,
+    pub error: Option<String>,
+    pub fetch: Vec<Task>,
+    pub compilation: Option<Compilation>,
+    pub tests: Vec<TestSuite>,
+    pub veredict: Option<Veredict>,
+    pub submission: Option<Submission>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct Task {
+    pub name: String,
+    pub status: ux::TaskStatus,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct Compilation {
+    pub compiler: String,
+    pub jutge_compiler: String,
+    pub status: ux::TaskStatus,
+    pub passes: Vec<CompilationPass>,
+}
+
+#[derive(Serialize)]
+pub struct CompilationPass {
+    pub pass: u8,
+    pub status: ux::TaskStatus,
+    pub stderr: String,
+    pub diagnostics: Vec<compilation::Diagnostic>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct TestSuite {
+    pub name: String,
+    pub tests: Vec<Test>,
+}
+
+#[derive(Serialize)]
+pub struct Test {
+    pub name: String,
+    pub status: ux::TaskStatus,
+    pub time: f64,
+    pub exit_code: Option<i32>,
+    pub signal: Option<i32>,
+    pub execution_error: Option<String>,
+    pub error: Option<String>,
+    pub stderr: String,
+    pub diff: Vec<testing::DiffHunk>,
+    pub sanitizer_report: Option<String>,
+}
+
+#[derive(Serialize, PartialEq, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum VeredictStatus {
+    DoesntCompile,
+    Untested,
+    DontSubmit,
+    ReadyToSubmit,
+}
+
+#[derive(Serialize)]
+pub struct Veredict {
+    pub status: VeredictStatus,
+    #[serde(flatten)]
+    pub summary: testing::Summary,
+}
+
+#[derive(Serialize)]
+pub struct Submission {
+    pub url: String,
+    pub status: ux::TaskStatus,
+    pub verdict: Option<String>,
+    pub description: Option<String>,
+}
+
+impl Report {
+    pub fn new(problem: &str) -> Report {
+        Report {
+            problem: problem.to_owned(),
+            error: None,
+            fetch: Vec::new(),
+            compilation: None,
+            tests: Vec::new(),
+            veredict: None,
+            submission: None,
+        }
+    }
+}
+
+pub fn print_json<T: Serialize + ?Sized>(value: &T) {
+    match serde_json::to_string_pretty(value) {
+        Ok(json) => println!("{}", json),
+        Err(e) => error!("Couldn't generate the JSON report: {}", e),
+    }
+}
+
+impl Compilation {
+    pub fn new(
+        backend: &dyn compilation::Backend,
+        result: &Result<Vec<compilation::CompilationPass>, compilation::CompileProcessError>,
+    ) -> Compilation {
+        let pass_report = |pass: &compilation::CompilationPass| CompilationPass {
+            pass: pass.pass,
+            status: ux::TaskStatus::Pass,
+            stderr: pass.stderr.clone(),
+            diagnostics: pass.diagnostics.clone(),
+            error: None,
+        };
+
+        match result {
+            Ok(passes) => Compilation {
+                compiler: backend.name().to_owned(),
+                jutge_compiler: backend.jutge_id().to_owned(),
+                status: ux::TaskStatus::Pass,
+                passes: passes.iter().map(pass_report).collect(),
+            },
+            Err(e) => {
+                let mut passes: Vec<CompilationPass> =
+                    e.completed_passes.iter().map(pass_report).collect();
+                passes.push(CompilationPass {
+                    pass: e.pass,
+                    status: ux::TaskStatus::Fail,
+                    stderr: match &e.error {
+                        compilation::Error::CompilerError(stderr) => stderr.clone(),
+                        _ => String::new(),
+                    },
+                    diagnostics: e.diagnostics.clone(),
+                    error: Some(e.error.to_string()),
+                });
+                Compilation {
+                    compiler: backend.name().to_owned(),
+                    jutge_compiler: backend.jutge_id().to_owned(),
+                    status: ux::TaskStatus::Fail,
+                    passes,
+                }
+            }
+        }
+    }
+}
+
+impl TestSuite {
+    pub fn new(suite: &testing::TestSuite, results: &[testing::TestResult]) -> TestSuite {
+        TestSuite {
+            name: suite.name().to_owned(),
+            tests: results
+                .iter()
+                .enumerate()
+                .map(|(i, result)| Test::new(suite.test_name(i), result))
+                .collect(),
+        }
+    }
+}
+
+impl Test {
+    pub fn new(name: String, result: &testing::TestResult) -> Test {
+        Test {
+            name,
+            status: result.status,
+            time: result.time.as_secs_f64(),
+            exit_code: result.exit_status.and_then(|s| s.code()),
+            signal: result.exit_status.and_then(|s| s.signal()),
+            execution_error: result.execution_error.map(|e| e.to_string()),
+            error: result.error.as_ref().map(|e| e.to_string()),
+            stderr: result.stderr.clone(),
+            sanitizer_report: result.sanitizer_report.clone(),
+            diff: result.diff.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn serialize_test_report() {
+        let test = Test::new(String::from("user test 1"), &testing::TestResult::skipped());
+        let json = serde_json::to_value(&test).unwrap();
+        assert_eq!(json["name"], "user test 1");
+        assert_eq!(json["status"], "skip_bad");
+        assert_eq!(json["exit_code"], serde_json::Value::Null);
+        assert!(json["diff"].as_array().unwrap().is_empty());
+    }
+}
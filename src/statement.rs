@@ -0,0 +1,73 @@
+use regex::Regex;
+
+/// Renders the HTML statement of a problem as plain text for the terminal
+pub fn to_text(html: &str) -> String {
+    let hidden = Regex::new(r"(?is)<(head|script|style)\b.*?</(head|script|style)>").unwrap();
+    let line_breaks = Regex::new(r"(?i)<br\s*/?>|</(p|div|h[1-6]|li|tr|pre|table|ul|ol)>").unwrap();
+    let headings = Regex::new(r"(?i)<h[1-6][^>]*>").unwrap();
+    let list_items = Regex::new(r"(?i)<li[^>]*>").unwrap();
+    let tags = Regex::new(r"(?s)<[^>]*>").unwrap();
+    let blank_lines = Regex::new(r"\n{3,}").unwrap();
+
+    let text = hidden.replace_all(html, "");
+    let text = line_breaks.replace_all(&text, "\n");
+    let text = headings.replace_all(&text, "\n\n");
+    let text = list_items.replace_all(&text, "- ");
+    let text = tags.replace_all(&text, "");
+    let text = decode_entities(&text);
+
+    let text: Vec<&str> = text.lines().map(str::trim_end).collect();
+    blank_lines
+        .replace_all(&text.join("\n"), "\n\n")
+        .trim()
+        .to_owned()
+}
+
+fn decode_entities(text: &str) -> String {
+    let numeric = Regex::new(r"&#(x[0-9a-fA-F]+|[0-9]+);").unwrap();
+    let text = numeric.replace_all(text, |captures: &regex::Captures| {
+        let code = &captures[1];
+        let code = match code.strip_prefix('x') {
+            Some(hex) => u32::from_str_radix(hex, 16).ok(),
+            None => code.parse().ok(),
+        };
+        code.and_then(char::from_u32)
+            .map_or(captures[0].to_owned(), String::from)
+    });
+
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn to_text_test() {
+        let html = "<html><head><title>Jutge.org</title><style>p { color: red; }</style></head>
+<body>
+<h1>Sum of two numbers</h1>
+<p>Write a program that reads <i>a</i>&nbsp;and <i>b</i>,<br>and prints a&nbsp;+&nbsp;b.</p>
+
+
+<ul><li>a &lt; 10<sup>9</sup></li><li>b &#8805; 0 &amp; b &#x3c; 5</li></ul>
+<script>alert('hi');</script>
+</body></html>";
+
+        assert_eq!(
+            to_text(html),
+            "Sum of two numbers
+
+Write a program that reads a and b,
+and prints a + b.
+
+- a < 109
+- b ≥ 0 & b < 5"
+        );
+    }
+}
@@ -1,9 +1,12 @@
 use crate::config::Config;
 use crate::{config, problem};
+use std::collections::HashMap;
 use std::path;
+use std::sync::OnceLock;
 use std::{env, fs, ops};
 
-static mut CONFIG: Option<Config> = None;
+static CONFIG: OnceLock<Config> = OnceLock::new();
+static PROBLEM_CONFIGS: OnceLock<HashMap<String, Config>> = OnceLock::new();
 
 pub struct SelfCleaningTmp {
     dir: path::PathBuf,
@@ -39,20 +42,29 @@ pub fn get_tests_folder() -> path::PathBuf {
 }
 
 pub fn get_config() -> config::Config {
-    unsafe {
-        if CONFIG.is_none() {
-            CONFIG = Some(config::Config::generate().unwrap());
-        }
-        CONFIG.clone().unwrap()
-    }
+    CONFIG
+        .get_or_init(|| config::Config::generate_from(["advocat"]).unwrap())
+        .clone()
 }
 
-pub fn try_get_problem(id: &str) -> Result<problem::Problem, problem::Error> {
+pub fn get_problem_config(id: &str) -> config::Config {
     let mut config = get_config();
     config.problem_dir = get_tests_folder().join("problems").join(id);
-    problem::Problem::new(&config)
+    config
 }
 
-pub fn get_problem(id: &str) -> problem::Problem {
-    try_get_problem(id).expect("Couldn't generate a problem struct for the test")
+/// The problems borrow their config, so there's one for each folder in tests/problems
+pub fn get_problem(id: &str) -> problem::Problem<'static> {
+    let configs = PROBLEM_CONFIGS.get_or_init(|| {
+        fs::read_dir(get_tests_folder().join("problems"))
+            .expect("Couldn't read the test problems")
+            .map(|entry| {
+                let id = entry.unwrap().file_name().to_string_lossy().to_string();
+                let config = get_problem_config(&id);
+                (id, config)
+            })
+            .collect()
+    });
+    let config = configs.get(id).expect("Unknown test problem");
+    problem::Problem::new(config).expect("Couldn't generate a problem struct for the test")
 }
@@ -0,0 +1,138 @@
+use crate::ux;
+use std::os::unix::process::ExitStatusExt;
+use std::{fmt, process};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ExecutionError {
+    TimeLimitExceeded,
+    MemoryError,
+    Killed,
+    UncaughtException,
+    SegmentationFault,
+    FloatingPointException,
+    Aborted,
+    Signal(i32),
+    NonZeroExitCode(i32),
+}
+
+impl fmt::Display for ExecutionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExecutionError::TimeLimitExceeded => write!(f, "Time limit exceeded"),
+            ExecutionError::MemoryError => write!(f, "Memory error (std::bad_alloc was thrown)"),
+            ExecutionError::Killed => write!(f, "The program was killed"),
+            ExecutionError::UncaughtException => {
+                write!(f, "Execution Error (uncaught exception)")
+            }
+            ExecutionError::SegmentationFault => write!(f, "Segmentation fault"),
+            ExecutionError::FloatingPointException => {
+                write!(f, "Floating point exception (division by zero?)")
+            }
+            ExecutionError::Aborted => write!(f, "Aborted (failed assertion?)"),
+            ExecutionError::Signal(signal) => {
+                write!(f, "The program was terminated by signal {}", signal)
+            }
+            ExecutionError::NonZeroExitCode(code) => write!(f, "Non-zero exit code ({})", code),
+        }
+    }
+}
+
+impl ExecutionError {
+    pub fn from_exit_status(status: &process::ExitStatus) -> Option<ExecutionError> {
+        if let Some(signal) = status.signal() {
+            Some(match signal {
+                libc::SIGXCPU => ExecutionError::TimeLimitExceeded,
+                // The stub raises SIGUSR1 on bad_alloc and SIGUSR2 on any other exception
+                libc::SIGUSR1 => ExecutionError::MemoryError,
+                libc::SIGUSR2 => ExecutionError::UncaughtException,
+                libc::SIGKILL => ExecutionError::Killed,
+                libc::SIGSEGV | libc::SIGBUS => ExecutionError::SegmentationFault,
+                libc::SIGFPE => ExecutionError::FloatingPointException,
+                libc::SIGABRT => ExecutionError::Aborted,
+                signal => ExecutionError::Signal(signal),
+            })
+        } else {
+            match status.code() {
+                Some(0) | None => None,
+                Some(code) => Some(ExecutionError::NonZeroExitCode(code)),
+            }
+        }
+    }
+
+    pub fn task_status(&self) -> ux::TaskStatus {
+        match self {
+            ExecutionError::TimeLimitExceeded => ux::TaskStatus::TimeLimitExceeded,
+            ExecutionError::MemoryError => ux::TaskStatus::MemoryLimitExceeded,
+            _ => ux::TaskStatus::ExecutionError,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn from_signal(signal: i32) -> Option<ExecutionError> {
+        ExecutionError::from_exit_status(&process::ExitStatus::from_raw(signal))
+    }
+
+    fn from_code(code: i32) -> Option<ExecutionError> {
+        ExecutionError::from_exit_status(&process::ExitStatus::from_raw(code << 8))
+    }
+
+    #[test]
+    fn from_exit_status_test() {
+        assert_eq!(from_code(0), None);
+        assert_eq!(from_code(3), Some(ExecutionError::NonZeroExitCode(3)));
+        assert_eq!(
+            from_signal(libc::SIGXCPU),
+            Some(ExecutionError::TimeLimitExceeded)
+        );
+        assert_eq!(
+            from_signal(libc::SIGUSR1),
+            Some(ExecutionError::MemoryError)
+        );
+        assert_eq!(
+            from_signal(libc::SIGUSR2),
+            Some(ExecutionError::UncaughtException)
+        );
+        assert_eq!(from_signal(libc::SIGKILL), Some(ExecutionError::Killed));
+        assert_eq!(
+            from_signal(libc::SIGSEGV),
+            Some(ExecutionError::SegmentationFault)
+        );
+        assert_eq!(
+            from_signal(libc::SIGFPE),
+            Some(ExecutionError::FloatingPointException)
+        );
+        assert_eq!(from_signal(libc::SIGABRT), Some(ExecutionError::Aborted));
+        assert_eq!(
+            from_signal(libc::SIGTERM),
+            Some(ExecutionError::Signal(libc::SIGTERM))
+        );
+    }
+
+    #[test]
+    fn task_status_test() {
+        assert_eq!(
+            ExecutionError::TimeLimitExceeded.task_status(),
+            ux::TaskStatus::TimeLimitExceeded
+        );
+        assert_eq!(
+            ExecutionError::MemoryError.task_status(),
+            ux::TaskStatus::MemoryLimitExceeded
+        );
+        assert_eq!(
+            ExecutionError::Killed.task_status(),
+            ux::TaskStatus::ExecutionError
+        );
+        assert_eq!(
+            ExecutionError::SegmentationFault.task_status(),
+            ux::TaskStatus::ExecutionError
+        );
+        assert_eq!(
+            ExecutionError::NonZeroExitCode(1).task_status(),
+            ux::TaskStatus::ExecutionError
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use std::os::unix::process::CommandExt;
+use std::{io, process, time};
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+type Resource = libc::__rlimit_resource_t;
+#[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+type Resource = libc::c_int;
+
+const DEFAULT_TIME_LIMIT: time::Duration = time::Duration::from_secs(10);
+const DEFAULT_CPU_LIMIT: u64 = 5;
+const DEFAULT_MEMORY_LIMIT: u64 = 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Limits {
+    pub time: time::Duration,
+    pub cpu_time: u64,
+    pub memory: u64,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            time: DEFAULT_TIME_LIMIT,
+            cpu_time: DEFAULT_CPU_LIMIT,
+            memory: DEFAULT_MEMORY_LIMIT,
+        }
+    }
+}
+
+impl Limits {
+    /// Some runtimes (like the JVM) and the sanitizers reserve much more virtual memory than they
+    /// use, which the memory limit forbids
+    pub fn without_memory_limit(&self) -> Limits {
+        Limits {
+            memory: u64::MAX,
+            ..*self
+        }
+    }
+
+    pub fn apply(&self, command: &mut process::Command) {
+        let cpu_time = self.cpu_time as libc::rlim_t;
+        let memory = self.memory.saturating_mul(1024 * 1024) as libc::rlim_t;
+
+        // SAFETY: The closure only calls setrlimit, which is async-signal-safe
+        unsafe {
+            command.pre_exec(move || {
+                // The soft limit sends SIGXCPU, the hard one a SIGKILL if it's ignored
+                set_limit(libc::RLIMIT_CPU, cpu_time, cpu_time + 1)?;
+                // Allocations over this limit fail, making the stub raise SIGUSR1
+                set_limit(libc::RLIMIT_AS, memory, memory)
+            });
+        }
+    }
+}
+
+fn set_limit(resource: Resource, soft: libc::rlim_t, hard: libc::rlim_t) -> io::Result<()> {
+    let limit = libc::rlimit {
+        rlim_cur: soft,
+        rlim_max: hard,
+    };
+
+    if unsafe { libc::setrlimit(resource, &limit) } == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
@@ -1,6 +1,13 @@
 mod diff_display;
+mod execution_error;
+mod limits;
+mod program;
 mod test;
 mod testsuite;
 
+pub use limits::Limits;
+pub use program::Program;
+pub use test::{DiffHunk, DiffKind, TestResult};
 pub use testsuite::Error;
+pub use testsuite::Summary;
 pub use testsuite::TestSuite;
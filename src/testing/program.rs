@@ -0,0 +1,58 @@
+use crate::testing::limits;
+use std::{ffi, path, process};
+
+pub struct Program {
+    command: ffi::OsString,
+    args: Vec<ffi::OsString>,
+    heap_flag: Option<&'static str>,
+}
+
+impl Program {
+    pub fn new(command: &str, args: &[&ffi::OsStr]) -> Program {
+        Program {
+            command: command.into(),
+            args: args.iter().map(|&a| a.to_owned()).collect(),
+            heap_flag: None,
+        }
+    }
+
+    /// A runtime that reserves much more virtual memory than it uses, so instead of limiting its
+    /// address space the memory limit is given to it with `heap_flag` (in megabytes)
+    pub fn virtual_machine(
+        command: &str,
+        heap_flag: &'static str,
+        args: &[&ffi::OsStr],
+    ) -> Program {
+        Program {
+            heap_flag: Some(heap_flag),
+            ..Program::new(command, args)
+        }
+    }
+
+    pub fn binary(binary: &path::Path) -> Program {
+        Program {
+            command: binary.as_os_str().to_owned(),
+            args: Vec::new(),
+            heap_flag: None,
+        }
+    }
+
+    pub fn interpreted(interpreter: &str, script: &path::Path) -> Program {
+        Program::new(interpreter, &[script.as_os_str()])
+    }
+
+    pub fn command(&self, limits: &limits::Limits) -> process::Command {
+        let mut command = process::Command::new(&self.command);
+        match self.heap_flag {
+            Some(flag) => {
+                if limits.memory != u64::MAX {
+                    command.arg(format!("{}{}m", flag, limits.memory));
+                }
+                limits.without_memory_limit().apply(&mut command);
+            }
+            None => limits.apply(&mut command),
+        }
+        command.args(&self.args);
+        command
+    }
+}
@@ -1,8 +1,10 @@
-use crate::testing::diff_display;
+use crate::testing::{diff_display, execution_error, limits, program};
 use crate::{debug, ux};
-use std::io::Write;
-use std::{fs, io, path, process};
+use serde::Serialize;
+use std::io::{Read, Write};
+use std::{fs, io, path, process, thread, time};
 use termion::color;
+use wait_timeout::ChildExt;
 
 pub struct Test {
     inputs: String,
@@ -12,7 +14,26 @@ pub struct Test {
 pub struct TestResult {
     pub status: ux::TaskStatus,
     pub error: Option<io::Error>,
-    pub diff: String,
+    pub execution_error: Option<execution_error::ExecutionError>,
+    pub exit_status: Option<process::ExitStatus>,
+    pub time: time::Duration,
+    pub diff: Vec<DiffHunk>,
+    pub stderr: String,
+    pub sanitizer_report: Option<String>,
+}
+
+#[derive(Serialize, PartialEq, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffKind {
+    Both,
+    Expected,
+    Output,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct DiffHunk {
+    pub kind: DiffKind,
+    pub lines: Vec<String>,
 }
 
 impl Test {
@@ -30,72 +51,154 @@ impl Test {
         None
     }
 
-    pub fn run(&self, binary: &path::Path) -> TestResult {
-        debug!("Executing the binary");
-        let process = process::Command::new(binary)
+    pub fn run(&self, program: &program::Program, limits: &limits::Limits) -> TestResult {
+        debug!("Executing the program");
+        let mut command = program.command(limits);
+        command
             .stdin(process::Stdio::piped())
             .stdout(process::Stdio::piped())
-            .stderr(process::Stdio::piped())
-            .spawn();
+            .stderr(process::Stdio::piped());
 
-        let mut process = match process {
+        let start = time::Instant::now();
+        let mut process = match command.spawn() {
             Ok(p) => p,
-            Err(e) => {
-                return TestResult {
-                    status: ux::TaskStatus::Fail,
-                    error: Some(e),
-                    diff: String::new(),
-                }
-            }
+            Err(e) => return TestResult::from_error(e),
         };
 
         debug!("Sending inputs");
-        match process.stdin.take() {
-            Some(mut stdin) => {
-                if let Err(e) = stdin.write(self.inputs.as_bytes()) {
-                    return TestResult {
-                        status: ux::TaskStatus::Fail,
-                        error: Some(e),
-                        diff: String::new(),
-                    };
-                }
+        let stdin_writer = match process.stdin.take() {
+            Some(stdin) => Some(write_pipe(stdin, self.inputs.clone())),
+            None => {
+                debug!("The input pipe was closed by the program!");
+                None
             }
-            None => debug!("The input pipe was closed by the program!"),
         };
+        let stdout_reader = process.stdout.take().map(read_pipe);
+        let stderr_reader = process.stderr.take().map(read_pipe);
 
         debug!("Waiting for the program to end");
-        let output = match process.wait_with_output() {
-            Ok(o) => o,
-            Err(e) => {
-                return TestResult {
-                    status: ux::TaskStatus::Fail,
-                    error: Some(e),
-                    diff: String::new(),
+        let exit_status = match process.wait_timeout(limits.time) {
+            Ok(Some(status)) => Some(status),
+            Ok(None) => {
+                debug!("The program exceeded the time limit, killing it");
+                if let Err(e) = process.kill().and_then(|_| process.wait()) {
+                    return TestResult::from_error(e);
                 }
+                None
             }
+            Err(e) => return TestResult::from_error(e),
         };
+        let elapsed = start.elapsed();
+
+        if let Some(Err(e)) = stdin_writer.map(|w| w.join().unwrap()) {
+            debug!("Couldn't send all the inputs to the program: {}", e);
+        }
 
         debug!("Capturing output");
-        let binary_output = String::from_utf8_lossy(&output.stdout).to_string();
+        let stdout = stdout_reader.map(|r| r.join().unwrap()).unwrap_or_default();
+        let stderr = stderr_reader.map(|r| r.join().unwrap()).unwrap_or_default();
+
+        debug!("Decoding the exit status");
+        let execution_error = match exit_status {
+            Some(status) => execution_error::ExecutionError::from_exit_status(&status),
+            None => Some(execution_error::ExecutionError::TimeLimitExceeded),
+        };
+
+        let program_output = String::from_utf8_lossy(&stdout).to_string();
 
         debug!("Computing diff");
-        let (pass, diff) = parse_diff(diff::lines(&self.outputs, &binary_output));
-        let status = if pass {
-            ux::TaskStatus::Pass
-        } else {
-            ux::TaskStatus::Fail
+        let diff = parse_diff(diff::lines(&self.outputs, &program_output));
+        let pass = diff.iter().all(|hunk| hunk.kind == DiffKind::Both);
+        let status = match execution_error {
+            Some(e) => e.task_status(),
+            None if pass => ux::TaskStatus::Pass,
+            None => ux::TaskStatus::Fail,
         };
         TestResult {
             status,
             error: None,
+            execution_error,
+            exit_status,
+            time: elapsed,
             diff,
+            stderr: String::from_utf8_lossy(&stderr).to_string(),
+            sanitizer_report: None,
+        }
+    }
+}
+
+impl TestResult {
+    pub fn is_failure(&self) -> bool {
+        self.error.is_none() && !self.status.is_ok() && self.status != ux::TaskStatus::SkipBad
+    }
+
+    fn from_error(error: io::Error) -> TestResult {
+        TestResult {
+            error: Some(error),
+            ..TestResult::from_status(ux::TaskStatus::Fail)
         }
     }
+
+    pub fn skipped() -> TestResult {
+        TestResult::from_status(ux::TaskStatus::SkipBad)
+    }
+
+    fn from_status(status: ux::TaskStatus) -> TestResult {
+        TestResult {
+            status,
+            error: None,
+            execution_error: None,
+            exit_status: None,
+            time: time::Duration::ZERO,
+            diff: Vec::new(),
+            stderr: String::new(),
+            sanitizer_report: None,
+        }
+    }
+
+    pub fn render_diff(&self) -> String {
+        render_diff(&self.diff)
+    }
+}
+
+fn write_pipe<W: Write + Send + 'static>(
+    mut pipe: W,
+    contents: String,
+) -> thread::JoinHandle<io::Result<()>> {
+    thread::spawn(move || pipe.write_all(contents.as_bytes()))
+}
+
+fn read_pipe<R: Read + Send + 'static>(mut pipe: R) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut contents = Vec::new();
+        let _ = pipe.read_to_end(&mut contents);
+        contents
+    })
 }
 
-fn parse_diff(diff: Vec<diff::Result<&str>>) -> (bool, String) {
+fn parse_diff(diff: Vec<diff::Result<&str>>) -> Vec<DiffHunk> {
     debug!("Parsing diff");
-    let mut pass = true;
+    let mut hunks: Vec<DiffHunk> = Vec::new();
+
+    for line in diff {
+        let (kind, line) = match line {
+            diff::Result::Left(l) => (DiffKind::Expected, l),
+            diff::Result::Both(l, _) => (DiffKind::Both, l),
+            diff::Result::Right(r) => (DiffKind::Output, r),
+        };
+        match hunks.last_mut() {
+            Some(hunk) if hunk.kind == kind => hunk.lines.push(line.to_owned()),
+            _ => hunks.push(DiffHunk {
+                kind,
+                lines: vec![line.to_owned()],
+            }),
+        }
+    }
+
+    hunks
+}
+
+fn render_diff(hunks: &[DiffHunk]) -> String {
     let mut dd = diff_display::DiffDisplay::new(
         "Expected output",
         "Your output",
@@ -103,22 +206,82 @@ fn parse_diff(diff: Vec<diff::Result<&str>>) -> (bool, String) {
         &color::Red,
     );
 
-    for line in diff {
-        match line {
-            diff::Result::Left(l) => {
-                pass = false;
-                dd.write_left(l);
-            }
-            diff::Result::Both(l, r) => {
-                dd.write_both(l, r);
-            }
-            diff::Result::Right(r) => {
-                pass = false;
-                dd.write_right(r);
+    for hunk in hunks {
+        for line in &hunk.lines {
+            match hunk.kind {
+                DiffKind::Expected => dd.write_left(line),
+                DiffKind::Both => dd.write_both(line, line),
+                DiffKind::Output => dd.write_right(line),
             }
         }
     }
 
     dd.end();
-    (pass, dd.build())
+    dd.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils;
+    use std::time;
+
+    fn get_test(inputs: &str, outputs: &str) -> Test {
+        Test {
+            inputs: inputs.to_owned(),
+            outputs: outputs.to_owned(),
+        }
+    }
+
+    fn get_limits(seconds: u64) -> limits::Limits {
+        limits::Limits {
+            time: time::Duration::from_secs(seconds),
+            cpu_time: seconds,
+            memory: 64,
+        }
+    }
+
+    #[test]
+    fn run_pass_test() {
+        let test = get_test("1 2 3\n", "1 2 3\n");
+        let result = test.run(
+            &program::Program::binary(path::Path::new("cat")),
+            &get_limits(5),
+        );
+        assert_eq!(result.status, ux::TaskStatus::Pass);
+        assert!(result.error.is_none());
+    }
+
+    #[test]
+    fn run_fail_test() {
+        let test = get_test("1 2 3\n", "3 2 1\n");
+        let result = test.run(
+            &program::Program::binary(path::Path::new("cat")),
+            &get_limits(5),
+        );
+        assert_eq!(result.status, ux::TaskStatus::Fail);
+        assert!(result.error.is_none());
+    }
+
+    #[test]
+    fn run_time_limit_test() {
+        let binary = test_utils::get_tests_folder().join("resources/programs/infinite_loop.sh");
+        let test = get_test("", "");
+        let result = test.run(&program::Program::binary(&binary), &get_limits(1));
+        assert_eq!(result.status, ux::TaskStatus::TimeLimitExceeded);
+    }
+
+    #[test]
+    fn run_execution_error_test() {
+        let test = get_test("", "");
+        let result = test.run(
+            &program::Program::binary(path::Path::new("false")),
+            &get_limits(5),
+        );
+        assert_eq!(result.status, ux::TaskStatus::ExecutionError);
+        assert_eq!(
+            result.execution_error,
+            Some(execution_error::ExecutionError::NonZeroExitCode(1))
+        );
+    }
 }
@@ -1,9 +1,14 @@
-use crate::testing::test;
+use crate::testing::execution_error::ExecutionError;
+use crate::testing::{limits, program, test};
 use crate::{error, ux};
+use serde::Serialize;
 use std::fmt;
 use std::fs;
 use std::io;
+use std::ops;
 use std::path;
+use std::sync;
+use std::thread;
 use termion::style;
 
 pub enum Error {
@@ -22,6 +27,25 @@ impl fmt::Display for Error {
     }
 }
 
+#[derive(Default, Debug, PartialEq, Serialize)]
+pub struct Summary {
+    pub passed: usize,
+    pub total: usize,
+    pub time_limit_exceeded: usize,
+    pub memory_limit_exceeded: usize,
+    pub execution_errors: usize,
+}
+
+impl ops::AddAssign for Summary {
+    fn add_assign(&mut self, rhs: Self) {
+        self.passed += rhs.passed;
+        self.total += rhs.total;
+        self.time_limit_exceeded += rhs.time_limit_exceeded;
+        self.memory_limit_exceeded += rhs.memory_limit_exceeded;
+        self.execution_errors += rhs.execution_errors;
+    }
+}
+
 pub struct TestSuite {
     name: String,
     tests: Vec<test::Test>,
@@ -57,33 +81,214 @@ impl TestSuite {
         }
     }
 
-    pub fn run(&self, binary: &path::Path, should_skip: bool) -> usize {
-        let mut pass_count: usize = 0;
-        for (i, test) in self.tests.iter().enumerate() {
-            let test_name = format!("{} test {}", self.name, i + 1);
-            if should_skip {
-                ux::show_task_status(&test_name, ux::TaskType::Test, &ux::TaskStatus::SkipBad);
-            } else {
-                ux::show_task_status(&test_name, ux::TaskType::Test, &ux::TaskStatus::InProgress);
-                let result = test.run(binary);
-                ux::show_task_status(&test_name, ux::TaskType::Test, &result.status);
-                if let Some(e) = result.error {
-                    error!("Error running test: {}", e);
-                } else if result.status.is_ok() {
-                    pass_count += 1;
-                } else {
-                    ux::show_task_output(
-                        "Test diff",
-                        format!("{}{}", style::Reset, result.diff).as_str(),
-                    );
+    pub fn run(
+        &self,
+        program: &program::Program,
+        sanitized: Option<&program::Program>,
+        should_skip: bool,
+        limits: &limits::Limits,
+        jobs: usize,
+    ) -> (Summary, Vec<test::TestResult>) {
+        let mut summary = Summary {
+            total: self.count(),
+            ..Default::default()
+        };
+        let mut shown_results = Vec::with_capacity(self.count());
+
+        if should_skip {
+            for i in 0..self.count() {
+                let result = test::TestResult::skipped();
+                ux::show_task_status(&self.test_name(i), ux::TaskType::Test, &result.status);
+                shown_results.push(result);
+            }
+            return (summary, shown_results);
+        }
+
+        let next_test = sync::atomic::AtomicUsize::new(0);
+        let (sender, receiver) = sync::mpsc::channel();
+
+        thread::scope(|scope| {
+            for _ in 0..jobs.clamp(1, self.count().max(1)) {
+                let sender = sender.clone();
+                let next_test = &next_test;
+                scope.spawn(move || loop {
+                    let i = next_test.fetch_add(1, sync::atomic::Ordering::Relaxed);
+                    match self.tests.get(i) {
+                        Some(test) => {
+                            let mut result = test.run(program, limits);
+                            // The sanitizers slow the program down, so a timeout would only repeat
+                            if let (Some(sanitized), true) = (
+                                sanitized,
+                                result.is_failure()
+                                    && result.status != ux::TaskStatus::TimeLimitExceeded,
+                            ) {
+                                let limits = limits.without_memory_limit();
+                                let sanitized_result = test.run(sanitized, &limits);
+                                result.sanitizer_report = Some(sanitized_result.stderr);
+                            }
+                            if sender.send((i, result)).is_err() {
+                                break;
+                            }
+                        }
+                        None => break,
+                    }
+                });
+            }
+            drop(sender);
+
+            // Results may arrive in any order, but they are shown in the original one
+            let mut results: Vec<Option<test::TestResult>> =
+                self.tests.iter().map(|_| None).collect();
+            let mut next_to_show = 0;
+            self.show_in_progress(next_to_show);
+            for (i, result) in receiver {
+                results[i] = Some(result);
+                while let Some(result) = results.get_mut(next_to_show).and_then(Option::take) {
+                    self.show_result(next_to_show, &result, &mut summary);
+                    shown_results.push(result);
+                    next_to_show += 1;
+                    self.show_in_progress(next_to_show);
                 }
             }
+        });
+
+        (summary, shown_results)
+    }
+
+    fn show_in_progress(&self, i: usize) {
+        if i < self.count() {
+            ux::show_task_status(
+                &self.test_name(i),
+                ux::TaskType::Test,
+                &ux::TaskStatus::InProgress,
+            );
         }
+    }
 
-        pass_count
+    fn show_result(&self, i: usize, result: &test::TestResult, summary: &mut Summary) {
+        ux::show_task_status(&self.test_name(i), ux::TaskType::Test, &result.status);
+        if let Some(e) = &result.error {
+            error!("Error running test: {}", e);
+        } else if result.status.is_ok() {
+            summary.passed += 1;
+        } else {
+            match result.status {
+                ux::TaskStatus::TimeLimitExceeded => summary.time_limit_exceeded += 1,
+                ux::TaskStatus::MemoryLimitExceeded => summary.memory_limit_exceeded += 1,
+                ux::TaskStatus::ExecutionError => summary.execution_errors += 1,
+                _ => {}
+            }
+            show_test_failure(result);
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn test_name(&self, i: usize) -> String {
+        format!("{} test {}", self.name, i + 1)
     }
 
     pub fn count(&self) -> usize {
         self.tests.len()
     }
 }
+
+fn show_test_failure(result: &test::TestResult) {
+    match result.execution_error {
+        // The time limit is already reported by the task status
+        None | Some(ExecutionError::TimeLimitExceeded) => {}
+        Some(e) if result.stderr.is_empty() => {
+            ux::show_task_output("Execution error", &e.to_string())
+        }
+        Some(e) => ux::show_task_output(&e.to_string(), result.stderr.trim_end()),
+    }
+
+    if let ux::TaskStatus::Fail | ux::TaskStatus::ExecutionError = result.status {
+        ux::show_task_output(
+            "Test diff",
+            format!("{}{}", style::Reset, result.render_diff()).as_str(),
+        );
+    }
+
+    match result.sanitizer_report.as_deref().map(str::trim_end) {
+        None => {}
+        Some("") => ux::show_task_output("Sanitizer report", "No problems found"),
+        Some(report) => ux::show_task_output("Sanitizer report", report),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils;
+    use std::time;
+
+    fn cat() -> program::Program {
+        program::Program::binary(path::Path::new("cat"))
+    }
+
+    fn write_test(dir: &path::Path, name: &str, inputs: &str, outputs: &str) {
+        fs::write(dir.join(name).with_extension("inp"), inputs).unwrap();
+        fs::write(dir.join(name).with_extension("cor"), outputs).unwrap();
+    }
+
+    #[test]
+    fn run_parallel_test() {
+        let dir = test_utils::SelfCleaningTmp::new("testsuite", "run_parallel_test");
+        for i in 0..8 {
+            write_test(&dir, &format!("pass{}", i), "foo\n", "foo\n");
+        }
+        write_test(&dir, "fail", "foo\n", "bar\n");
+
+        let suite = TestSuite::from_dir("parallel", &dir).ok().unwrap();
+        assert_eq!(suite.count(), 9);
+
+        let (summary, results) = suite.run(&cat(), None, false, &limits::Limits::default(), 4);
+        assert_eq!(summary.passed, 8);
+        assert_eq!(summary.total, 9);
+        assert_eq!(results.len(), 9);
+        assert_eq!(results[0].status, ux::TaskStatus::Fail);
+    }
+
+    #[test]
+    fn run_sanitized_test() {
+        let dir = test_utils::SelfCleaningTmp::new("testsuite", "run_sanitized_test");
+        write_test(&dir, "fail", "foo\n", "bar\n");
+        write_test(&dir, "pass", "foo\n", "foo\n");
+
+        let suite = TestSuite::from_dir("sanitized", &dir).ok().unwrap();
+        let sanitized = program::Program::new("sh", &["-c".as_ref(), "echo report >&2".as_ref()]);
+        let (_, results) = suite.run(
+            &cat(),
+            Some(&sanitized),
+            false,
+            &limits::Limits::default(),
+            2,
+        );
+        assert_eq!(results[0].sanitizer_report.as_deref(), Some("report\n"));
+        assert_eq!(results[1].sanitizer_report, None);
+
+        let slow = program::Program::new("sh", &["-c".as_ref(), "sleep 1".as_ref()]);
+        let limits = limits::Limits {
+            time: time::Duration::from_millis(100),
+            ..Default::default()
+        };
+        let (_, results) = suite.run(&slow, Some(&sanitized), false, &limits, 2);
+        assert_eq!(results[0].status, ux::TaskStatus::TimeLimitExceeded);
+        assert_eq!(results[0].sanitizer_report, None);
+    }
+
+    #[test]
+    fn run_skip_test() {
+        let dir = test_utils::SelfCleaningTmp::new("testsuite", "run_skip_test");
+        write_test(&dir, "sample", "foo\n", "foo\n");
+
+        let suite = TestSuite::from_dir("skipped", &dir).ok().unwrap();
+        let (summary, results) = suite.run(&cat(), None, true, &limits::Limits::default(), 4);
+        assert_eq!(summary.passed, 0);
+        assert_eq!(summary.total, 1);
+        assert_eq!(results[0].status, ux::TaskStatus::SkipBad);
+    }
+}
@@ -1,3 +1,4 @@
+use serde::Serialize;
 use std::io;
 use std::io::Write;
 use termion::{color, style};
@@ -10,7 +11,14 @@ pub enum LogLevel {
     Error,
 }
 
+#[derive(PartialEq, Clone, Copy)]
+pub enum OutputFormat {
+    Human,
+    Json,
+}
+
 static mut GLOBAL_LOG_LEVEL: LogLevel = LogLevel::Info;
+static mut GLOBAL_OUTPUT_FORMAT: OutputFormat = OutputFormat::Human;
 
 pub fn set_global_log_level(level: LogLevel) {
     unsafe { GLOBAL_LOG_LEVEL = level }
@@ -20,11 +28,24 @@ pub fn get_global_log_level() -> LogLevel {
     unsafe { GLOBAL_LOG_LEVEL }
 }
 
+pub fn set_global_output_format(format: OutputFormat) {
+    unsafe { GLOBAL_OUTPUT_FORMAT = format }
+}
+
+pub fn get_global_output_format() -> OutputFormat {
+    unsafe { GLOBAL_OUTPUT_FORMAT }
+}
+
+pub fn is_human_output() -> bool {
+    get_global_output_format() == OutputFormat::Human
+}
+
 pub fn print_message(level: LogLevel, msg: String) {
     match level {
         x if x < get_global_log_level() => (),
         LogLevel::Debug => eprintln!(":: {}{}{}", style::Italic, msg, style::Reset),
-        LogLevel::Info => println!("{}", msg),
+        LogLevel::Info if is_human_output() => println!("{}", msg),
+        LogLevel::Info => eprintln!("{}", msg),
         LogLevel::Warning => eprintln!(
             "{}{}WARNING: {}{}{}",
             color::Fg(color::LightYellow),
@@ -74,13 +95,17 @@ pub enum TaskType {
     Test,
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum TaskStatus {
     Done,
     Pass,
     SkipGood,
     SkipBad,
     Fail,
+    TimeLimitExceeded,
+    MemoryLimitExceeded,
+    ExecutionError,
     InProgress,
 }
 
@@ -94,6 +119,10 @@ impl TaskStatus {
 }
 
 pub fn show_task_status(name: &str, task_type: TaskType, task_status: &TaskStatus) {
+    if !is_human_output() {
+        return;
+    }
+
     let name = match task_type {
         TaskType::Fetch => name.to_owned() + "... ",
         TaskType::Test => name.to_uppercase() + ": ",
@@ -106,6 +135,19 @@ pub fn show_task_status(name: &str, task_type: TaskType, task_status: &TaskStatu
         TaskStatus::SkipGood => println!("{}SKIP ✓{}", color::Fg(color::Cyan), style::Reset),
         TaskStatus::SkipBad => println!("{}SKIP ✘{}", color::Fg(color::Cyan), style::Reset),
         TaskStatus::Fail => println!("{}FAIL ✘{}", color::Fg(color::Red), style::Reset),
+        TaskStatus::TimeLimitExceeded => println!(
+            "{}TIME LIMIT EXCEEDED ✘{}",
+            color::Fg(color::Red),
+            style::Reset
+        ),
+        TaskStatus::MemoryLimitExceeded => println!(
+            "{}MEMORY LIMIT EXCEEDED ✘{}",
+            color::Fg(color::Red),
+            style::Reset
+        ),
+        TaskStatus::ExecutionError => {
+            println!("{}EXECUTION ERROR ✘{}", color::Fg(color::Red), style::Reset)
+        }
         TaskStatus::InProgress => {
             print!("{}...\r", style::Reset);
             if let LogLevel::Debug = get_global_log_level() {
@@ -118,10 +160,33 @@ pub fn show_task_status(name: &str, task_type: TaskType, task_status: &TaskStatu
 }
 
 pub fn show_task_output(title: &str, contents: &str) {
+    if !is_human_output() {
+        return;
+    }
+
     println!("==> {}:", title);
     println!("{}{}{}", color::Fg(color::Magenta), contents, style::Reset);
 }
 
+/// Asks a yes/no question, answering no when nobody can answer it
+pub fn confirm(question: &str) -> bool {
+    if !is_human_output() || !termion::is_tty(&io::stdin()) {
+        return false;
+    }
+
+    print!("{}{} [y/N] {}", style::Bold, question, style::Reset);
+    io::stdout().flush().unwrap();
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer).is_ok() && is_yes(&answer)
+}
+
+fn is_yes(answer: &str) -> bool {
+    matches!(
+        answer.trim().to_lowercase().as_str(),
+        "y" | "yes" | "s" | "si" | "sí"
+    )
+}
+
 pub fn get_terminal_width() -> u16 {
     match terminal_size::terminal_size() {
         None => 100,
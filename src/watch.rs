@@ -0,0 +1,69 @@
+use crate::{debug, problem};
+use std::{fs, path, thread, time};
+
+const POLL_INTERVAL: time::Duration = time::Duration::from_millis(500);
+
+#[derive(PartialEq, Debug)]
+pub struct Snapshot {
+    files: Vec<(path::PathBuf, Option<time::SystemTime>)>,
+}
+
+impl Snapshot {
+    pub fn take(problem: &problem::Problem) -> Snapshot {
+        let mut files = vec![problem.source.clone()];
+        if let Some(dir) = problem.source.parent() {
+            if let Ok(entries) = fs::read_dir(dir) {
+                files.extend(entries.flatten().map(|e| e.path()).filter(|p| {
+                    let extension = p.extension().unwrap_or_default();
+                    extension == "inp" || extension == "cor"
+                }));
+            }
+        }
+        files.sort();
+
+        Snapshot {
+            files: files
+                .into_iter()
+                .map(|f| {
+                    let modified = fs::metadata(&f).and_then(|m| m.modified()).ok();
+                    (f, modified)
+                })
+                .collect(),
+        }
+    }
+}
+
+pub fn wait_for_changes(problem: &problem::Problem, snapshot: &mut Snapshot) {
+    loop {
+        thread::sleep(POLL_INTERVAL);
+        let current = Snapshot::take(problem);
+        if current != *snapshot {
+            debug!("Detected changes in the problem files");
+            *snapshot = current;
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils;
+
+    #[test]
+    fn snapshot_test() {
+        let tmp = test_utils::SelfCleaningTmp::new("watch", "snapshot_test");
+        let mut problem = test_utils::get_problem("P00000_xx");
+        problem.source = tmp.join("main.cc");
+        fs::write(&problem.source, "int main() {}\n").unwrap();
+
+        let snapshot = Snapshot::take(&problem);
+        assert_eq!(snapshot, Snapshot::take(&problem));
+
+        fs::write(tmp.join("sample.out"), "").unwrap();
+        assert_eq!(snapshot, Snapshot::take(&problem));
+
+        fs::write(tmp.join("sample.inp"), "").unwrap();
+        assert_ne!(snapshot, Snapshot::take(&problem));
+    }
+}
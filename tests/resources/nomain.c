@@ -0,0 +1,35 @@
+// C wrapper used for programs that don't require a main function
+
+#define main jutge__replaced__main
+
+#line 1 "/problem/main.c"
+// I'M THE ORIGINAL ONE
+#line 8 "/tmp/main.c"
+
+#undef main
+
+// START MAIN **************************
+
+#line 1 "/problem/downloaded/main.c"
+// I'M THE MAIN ONE
+#line 16 "/tmp/main.c"
+
+// END MAIN ****************************
+
+
+// START STUB **************************
+
+// Wrapper for C compiler that uses some tweaks to
+// speed up the program execution.
+
+#include <stdio.h>
+
+// the following function is executed before main
+__attribute__((constructor)) static void jutge__stub__initialization(void)
+{
+    // speedup io
+    static char jutge__stub__output_buffer[1 << 16];
+    setvbuf(stdout, jutge__stub__output_buffer, _IOFBF, sizeof jutge__stub__output_buffer);
+}
+
+// END STUB ****************************
\ No newline at end of file
@@ -2,13 +2,17 @@
 
 #define main jutge__replaced__main
 
+#line 1 "/problem/main.cc"
 // I'M THE ORIGINAL ONE
+#line 8 "/tmp/main.cc"
 
 #undef main
 
 // START MAIN **************************
 
+#line 1 "/problem/downloaded/main.cc"
 // I'M THE MAIN ONE
+#line 16 "/tmp/main.cc"
 
 // END MAIN ****************************
 
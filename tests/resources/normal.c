@@ -0,0 +1,22 @@
+// C wrapper used for programs that have a main function
+
+#line 1 "/problem/main.c"
+// I'M THE ORIGINAL ONE
+#line 6 "/tmp/main.c"
+
+// START STUB **************************
+
+// Wrapper for C compiler that uses some tweaks to
+// speed up the program execution.
+
+#include <stdio.h>
+
+// the following function is executed before main
+__attribute__((constructor)) static void jutge__stub__initialization(void)
+{
+    // speedup io
+    static char jutge__stub__output_buffer[1 << 16];
+    setvbuf(stdout, jutge__stub__output_buffer, _IOFBF, sizeof jutge__stub__output_buffer);
+}
+
+// END STUB ****************************
\ No newline at end of file
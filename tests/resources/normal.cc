@@ -1,6 +1,8 @@
 // C++ wrapper used for programs that have a main function
 
+#line 1 "/problem/main.cc"
 // I'M THE ORIGINAL ONE
+#line 6 "/tmp/main.cc"
 
 // START STUB **************************
 
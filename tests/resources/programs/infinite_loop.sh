@@ -0,0 +1,3 @@
+#!/bin/sh
+# Never ends, used to test the time limits
+while true; do :; done